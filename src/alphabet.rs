@@ -0,0 +1,189 @@
+use std::str::FromStr;
+
+use crate::{InvalidLetter, Letter, Word};
+
+// Every letter any alphabet can use; a Letter is an index into this table.
+// English letters come first so that their indices match the plain a-z offsets.
+pub const LETTERS: [char; 53] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z', 'à', 'á', 'â', 'ã', 'ä', 'ç', 'è', 'é', 'ê', 'ë', 'ì', 'í',
+    'î', 'ï', 'ñ', 'ò', 'ó', 'ô', 'õ', 'ö', 'ù', 'ú', 'û', 'ü', 'ý', 'ÿ', 'ß',
+];
+pub const MAX_LETTERS: usize = LETTERS.len();
+
+const ENGLISH: &str = "abcdefghijklmnopqrstuvwxyz";
+
+fn fold_accent(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' => 'a',
+        'ç' => 'c',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        _ => c,
+    }
+}
+
+fn lookup(c: char) -> Option<Letter> {
+    LETTERS
+        .iter()
+        .position(|l| *l == c)
+        .map(|i| Letter(i as u8))
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    letters: u64,
+    fold_accents: bool,
+}
+
+impl Alphabet {
+    pub fn new(letters: &str, fold_accents: bool) -> Result<Self, InvalidLetter> {
+        let mut mask = 0;
+        for c in letters.chars() {
+            match lookup(c) {
+                Some(letter) => mask |= 1 << letter.as_index(),
+                None => return Err(InvalidLetter(c)),
+            }
+        }
+        Ok(Self {
+            letters: mask,
+            fold_accents,
+        })
+    }
+    fn named(extra: &str, fold_accents: bool) -> Self {
+        Self::new(&format!("{}{}", ENGLISH, extra), fold_accents).unwrap()
+    }
+    pub fn english() -> Self {
+        Self::named("", false)
+    }
+    pub fn spanish() -> Self {
+        Self::named("ñáéíóúü", true)
+    }
+    pub fn french() -> Self {
+        Self::named("àâçéèêëîïôùûüÿ", true)
+    }
+    pub fn german() -> Self {
+        Self::named("äöüß", false)
+    }
+    pub fn portuguese() -> Self {
+        Self::named("áâãàçéêíóôõúü", true)
+    }
+    pub fn with_fold_accents(self, fold_accents: bool) -> Self {
+        Self {
+            fold_accents,
+            ..self
+        }
+    }
+    pub fn fold_accents(&self) -> bool {
+        self.fold_accents
+    }
+    pub fn contains(&self, letter: Letter) -> bool {
+        self.letters & (1 << letter.as_index()) != 0
+    }
+    pub fn letters(&self) -> Vec<Letter> {
+        (0..MAX_LETTERS)
+            .map(|i| Letter(i as u8))
+            .filter(|l| self.contains(*l))
+            .filter(|l| !self.fold_accents || fold_accent(l.as_char()) == l.as_char())
+            .collect()
+    }
+    pub fn size(&self) -> usize {
+        self.letters().len()
+    }
+    pub fn letter(&self, c: char) -> Result<Letter, InvalidLetter> {
+        let mut lower = c.to_lowercase();
+        let mut folded = match (lower.next(), lower.next()) {
+            (Some(l), None) => l,
+            _ => return Err(InvalidLetter(c)),
+        };
+        if self.fold_accents {
+            folded = fold_accent(folded);
+        }
+        match lookup(folded) {
+            Some(letter) if self.contains(letter) => Ok(letter),
+            _ => Err(InvalidLetter(c)),
+        }
+    }
+    pub fn word(&self, s: &str) -> Result<Word, InvalidLetter> {
+        Ok(Word {
+            vec: s
+                .chars()
+                .map(|c| self.letter(c))
+                .collect::<Result<Vec<Letter>, InvalidLetter>>()?,
+        })
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl FromStr for Alphabet {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let mut parts = s.split('+');
+        let alphabet = match &parts.next().unwrap().to_lowercase()[..] {
+            "english" => Self::english(),
+            "spanish" => Self::spanish(),
+            "french" => Self::french(),
+            "german" => Self::german(),
+            "portuguese" => Self::portuguese(),
+            _ => return Err("invalid alphabet".to_string()),
+        };
+        match parts.next() {
+            None => Ok(alphabet),
+            Some("fold") => Ok(alphabet.with_fold_accents(true)),
+            Some("accents") => Ok(alphabet.with_fold_accents(false)),
+            Some(_) => Err("invalid alphabet option".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_english_letters_match_ascii() {
+        for c in 'a'..='z' {
+            let letter = Alphabet::english().letter(c).unwrap();
+            assert_eq!(c as usize - 'a' as usize, letter.as_index());
+            assert_eq!(c, letter.as_char());
+        }
+        assert_eq!(26, Alphabet::english().size());
+        assert_eq!(Err(InvalidLetter('é')), Alphabet::english().letter('é'));
+    }
+
+    #[test]
+    fn test_spanish_keeps_enye() {
+        let spanish = Alphabet::spanish();
+        assert_eq!("arbol", spanish.word("Árbol").unwrap().to_string());
+        assert_eq!("señor", spanish.word("SEÑOR").unwrap().to_string());
+        assert_eq!(27, spanish.size());
+        let accents = spanish.with_fold_accents(false);
+        assert_eq!("árbol", accents.word("árbol").unwrap().to_string());
+    }
+
+    #[test]
+    fn test_german_eszett() {
+        let german = Alphabet::german();
+        assert_eq!("straße", german.word("Straße").unwrap().to_string());
+        assert_eq!("müde", german.word("müde").unwrap().to_string());
+        let folded: Alphabet = "german+fold".parse().unwrap();
+        assert_eq!("mude", folded.word("müde").unwrap().to_string());
+        assert_eq!(27, folded.size());
+    }
+
+    #[test]
+    fn test_french_folds_cedilla() {
+        let french: Alphabet = "french".parse().unwrap();
+        assert_eq!("garcon", french.word("garçon").unwrap().to_string());
+        assert_eq!(Err(InvalidLetter('ñ')), french.letter('ñ'));
+        assert!("klingon".parse::<Alphabet>().is_err());
+    }
+}
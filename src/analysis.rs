@@ -0,0 +1,122 @@
+use std::fmt;
+
+use crate::error::Error;
+use crate::game::Game;
+use crate::solver::{Solver, SolverMode};
+use crate::Word;
+
+#[derive(Debug, Clone)]
+pub struct GuessGrade {
+    pub guess: Word,
+    pub candidates_before: usize,
+    pub candidates_after: usize,
+    // expected number of candidates left after the guess, i.e. MinEV score / candidates
+    pub expected_after: f64,
+    pub best_guess: Word,
+    pub best_expected_after: f64,
+    pub expected_bits: f64,
+    // bits actually gained from the observed feedback
+    pub bits: f64,
+}
+
+impl GuessGrade {
+    // 1.0 when the guess was as good as the solver's, lower is worse.
+    pub fn skill(&self) -> f64 {
+        self.best_expected_after / self.expected_after
+    }
+    // Above 1.0 when fewer candidates were left than expected.
+    pub fn luck(&self) -> f64 {
+        self.expected_after / self.candidates_after as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameReport {
+    pub grades: Vec<GuessGrade>,
+}
+
+impl GameReport {
+    pub fn new(game: &Game) -> Result<Self, Error> {
+        let mode = SolverMode::MinEV;
+        let mut replay = game.clone();
+        replay.restart();
+        let mut solver = Solver::new(&mut replay);
+        let mut grades = vec![];
+        for result in &game.guesses {
+            let before = solver.possible_solutions.len();
+            let best_guess = solver.suggest(&mode)?;
+            let expected = |guess: &Word| solver.compute_score(guess, &mode) / before as f64;
+            let expected_after = expected(&result.guess);
+            let best_expected_after = expected(&best_guess);
+            let expected_bits = solver.expected_bits(&result.guess);
+            solver.observe(result.clone())?;
+            let after = solver.possible_solutions.len();
+            grades.push(GuessGrade {
+                guess: result.guess.clone(),
+                candidates_before: before,
+                candidates_after: after,
+                expected_after,
+                best_guess,
+                best_expected_after,
+                expected_bits,
+                bits: Solver::bits_gained(before, after),
+            });
+        }
+        Ok(Self { grades })
+    }
+}
+
+impl fmt::Display for GameReport {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            fmt,
+            "{:<8} {:>7} {:>7} {:>9}   {:<18} {:>6} {:>6} {:>11}",
+            "guess", "before", "after", "expected", "best (expected)", "skill", "luck", "bits"
+        )?;
+        for grade in &self.grades {
+            writeln!(
+                fmt,
+                "{:<8} {:>7} {:>7} {:>9.1}   {:<18} {:>5.0}% {:>5.2}x {:>5.2}/{:.2}",
+                grade.guess.to_string(),
+                grade.candidates_before,
+                grade.candidates_after,
+                grade.expected_after,
+                format!("{} ({:.1})", grade.best_guess, grade.best_expected_after),
+                100.0 * grade.skill(),
+                grade.luck(),
+                grade.bits,
+                grade.expected_bits,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::words::WordSource;
+
+    #[test]
+    fn test_game_report() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("relax".to_string()).unwrap();
+        for guess in &["roate", "lisle", "relax"] {
+            game.guess(guess.to_string()).unwrap();
+        }
+        let report = GameReport::new(&game).unwrap();
+        assert_eq!(3, report.grades.len());
+        let first = &report.grades[0];
+        assert_eq!(2315, first.candidates_before);
+        assert_eq!("roate", first.best_guess.to_string());
+        assert!((first.skill() - 1.0).abs() < 1e-9);
+        for pair in report.grades.windows(2) {
+            assert_eq!(pair[0].candidates_after, pair[1].candidates_before);
+            assert!(pair[1].skill() <= 1.0 + 1e-9);
+        }
+        assert_eq!(1, report.grades[2].candidates_after);
+        let bits: f64 = report.grades.iter().map(|g| g.bits).sum();
+        assert!((bits - 2315f64.log2()).abs() < 1e-9);
+        assert!((first.expected_bits - 5.883).abs() < 1e-3);
+    }
+}
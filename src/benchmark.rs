@@ -1,11 +1,21 @@
-use std::time::Instant;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use structopt::StructOpt;
 
 use wordsmith::counter::Counter;
+use wordsmith::daily::Puzzle;
+use wordsmith::families;
 use wordsmith::game::{Game, State};
-use wordsmith::solver::{Solver, SolverMode};
-use wordsmith::words::WordSource;
+use wordsmith::prior::Prior;
+use wordsmith::profile::Profile;
+use wordsmith::solver::{GuessPool, Solver, SolverMode, TieBreak};
+use wordsmith::words::{Lengths, WordList, WordSource};
+use wordsmith::Word;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "ws-benchmark")]
@@ -14,60 +24,493 @@ struct Opt {
     word_source: WordSource,
     #[structopt(short, long, default_value = "minev")]
     mode: SolverMode,
+    // prior over solutions used by the solver and for sampling: uniform or
+    // frequency[,CENTER[,WIDTH]]
+    #[structopt(short, long, default_value = "uniform")]
+    prior: Prior,
+    // solve N distinct solutions drawn from the prior instead of every solution, N per length
+    #[structopt(long, conflicts_with_all = &["solution", "solutions"])]
+    sample: Option<usize>,
+    // solve only these solutions
+    #[structopt(long)]
+    solution: Vec<String>,
+    // solve only the solutions listed in a file, one per line
+    #[structopt(long)]
+    solutions: Option<PathBuf>,
+    #[structopt(long)]
+    seed: Option<u64>,
+    // candidate, common, distinct, lexicographic, random[:SEED] or score:MODE
+    #[structopt(long, default_value = "candidate")]
+    tie_break: TieBreak,
+    // words the solver may guess: all, candidates or top,N
+    #[structopt(long, default_value = "all")]
+    guess_pool: GuessPool,
+    // file with extra allowed guesses, one per line
+    #[structopt(long)]
+    extra_guesses: Option<PathBuf>,
+    // print where the solver spends its time, per turn
+    #[structopt(long)]
+    profile: bool,
+    // list the N solutions needing the most guesses, besides every failed one
+    #[structopt(long, default_value = "0")]
+    worst: usize,
+    // rule out the answers of the puzzles before this one: today, a date or a puzzle number
+    #[structopt(long)]
+    exclude_before: Option<Puzzle>,
+    // rule out the answers listed in a file, one per line
+    #[structopt(long)]
+    used: Option<PathBuf>,
+    // solve words of each of these lengths, e.g. 4-11, from the word source
+    #[structopt(long)]
+    lengths: Option<Lengths>,
+    // also solve the same solutions with this mode and compare guesses and time
+    #[structopt(long)]
+    baseline: Option<SolverMode>,
+}
+
+// A solved or failed puzzle, with the candidates left after each guess.
+struct Run {
+    solution: String,
+    path: Vec<(String, usize)>,
+    solved: bool,
+}
+
+impl Run {
+    fn describe(&self) -> String {
+        let path: Vec<String> = self
+            .path
+            .iter()
+            .map(|(guess, left)| format!("{} ({})", guess, left))
+            .collect();
+        format!("{}: {}", self.solution, path.join(", "))
+    }
+}
+
+// Wilson score interval for a proportion of `k` out of `n`.
+fn wilson_interval(k: usize, n: usize, z: f64) -> (f64, f64) {
+    if n == 0 {
+        return (0.0, 1.0);
+    }
+    let (k, n) = (k as f64, n as f64);
+    let p = k / n;
+    let center = (p + z * z / (2.0 * n)) / (1.0 + z * z / n);
+    let margin = z / (1.0 + z * z / n) * (p * (1.0 - p) / n + z * z / (4.0 * n * n)).sqrt();
+    ((center - margin).max(0.0), (center + margin).min(1.0))
+}
+
+// Solutions of one word list, i.e. of one length, their prior weights and the size of the
+// guess pool on the first turn.
+struct Group {
+    wordlist: Rc<WordList>,
+    solutions: Vec<String>,
+    weights: Vec<f64>,
+    pool_size: usize,
+}
+
+// Runs of one group, the time they took and the first guess they shared.
+struct GroupRuns {
+    letter_count: usize,
+    runs: Vec<Run>,
+    elapsed: Duration,
+    opener: Option<Word>,
+}
+
+fn new_solver<'a>(game: &'a mut Game, opt: &Opt) -> Solver<'a> {
+    let mut solver: Solver = Solver::with_prior(game, &opt.prior);
+    solver.tie_break = opt.tie_break.clone();
+    solver.guess_pool = opt.guess_pool.clone();
+    solver
+}
+
+// Solves each solution with `mode`, printing the guesses or failure of each when `verbose`.
+// Returns the runs and their first guess, searched once for all the solutions when there is
+// no precomputed one.
+fn solve_all(
+    game: &mut Game,
+    solutions: &[String],
+    mode: &SolverMode,
+    opt: &Opt,
+    profile: &mut Profile,
+    verbose: bool,
+) -> (Vec<Run>, Option<Word>) {
+    game.restart();
+    let mut solver = new_solver(game, opt);
+    if opt.profile {
+        solver.enable_profiling();
+    }
+    let precomputed = solver.precomputed_first_guess(mode);
+    // without a precomputed first guess, e.g. for other lengths, search it only once
+    let opener = match precomputed {
+        Some(_) => None,
+        None => solver.suggest(mode).ok(),
+    };
+    // the search is profiled as part of the first turn
+    if let Some(p) = solver.profile() {
+        profile.merge(&p);
+    }
+    let first = precomputed.or_else(|| opener.clone());
+    let mut runs: Vec<Run> = Vec::new();
+    for (i, solution) in solutions.iter().enumerate() {
+        game.set_solution(solution.to_string()).unwrap();
+        game.restart();
+        let mut solver = new_solver(game, opt);
+        solver.opener = opener.clone();
+        if opt.profile {
+            solver.enable_profiling();
+        }
+        let mut path = Vec::new();
+        while let State::Unsolved = solver.game.state() {
+            match solver.guess(mode) {
+                Ok(result) => {
+                    path.push((result.guess.to_string(), solver.possible_solutions.len()))
+                }
+                Err(e) => {
+                    println!("Error solving {}: {}", solution, e);
+                    break;
+                }
+            }
+        }
+        if let Some(p) = solver.profile() {
+            profile.merge(&p);
+        }
+        let solved = matches!(solver.game.state(), State::Solved);
+        if verbose && solved {
+            let guesses: Vec<&str> = path.iter().map(|(g, _)| &g[..]).collect();
+            println!("{}", guesses.join(","));
+        } else if verbose {
+            println!(
+                "{}/{} Failed to solve {}.",
+                i + 1,
+                solutions.len(),
+                solution
+            );
+        }
+        runs.push(Run {
+            solution: solution.to_string(),
+            path,
+            solved,
+        });
+    }
+    (runs, first)
+}
+
+fn solve_groups(
+    game: &mut Game,
+    groups: &[Group],
+    mode: &SolverMode,
+    opt: &Opt,
+    profile: &mut Profile,
+    verbose: bool,
+) -> Vec<GroupRuns> {
+    groups
+        .iter()
+        .map(|group| {
+            game.set_wordlist(group.wordlist.clone());
+            let start = Instant::now();
+            let (runs, opener) = solve_all(game, &group.solutions, mode, opt, profile, verbose);
+            GroupRuns {
+                letter_count: game.letter_count,
+                runs,
+                elapsed: start.elapsed(),
+                opener,
+            }
+        })
+        .collect()
+}
+
+fn average(runs: &[&Run]) -> f64 {
+    let solved: Vec<usize> = runs
+        .iter()
+        .filter(|r| r.solved)
+        .map(|r| r.path.len())
+        .collect();
+    solved.iter().sum::<usize>() as f64 / solved.len().max(1) as f64
 }
 
 fn main() {
     let opt = Opt::from_args();
-    let mut game = Game::from_source(&opt.word_source);
+    let wordlists = match opt.lengths {
+        Some(lengths) => WordList::from_source_lengths(&opt.word_source, lengths),
+        None => WordList::from_source(&opt.word_source).map(|w| vec![w]),
+    };
+    let mut wordlists = wordlists.expect("failed to load word list");
+    let (mut added, mut excluded) = (0, 0);
+    for wordlist in wordlists.iter_mut() {
+        if let Some(path) = &opt.extra_guesses {
+            added += wordlist
+                .add_guesses_from_file(path)
+                .expect("failed to load extra guesses");
+        }
+        if let Some(puzzle) = &opt.exclude_before {
+            let number = puzzle.number().expect("invalid puzzle");
+            excluded += wordlist
+                .exclude_before(number)
+                .expect("failed to exclude used answers");
+        }
+        if let Some(path) = &opt.used {
+            excluded += wordlist
+                .exclude_solutions_from_file(path)
+                .expect("failed to load used answers");
+        }
+    }
+    if opt.extra_guesses.is_some() {
+        println!("Added {} extra guesses.", added);
+    }
+    if excluded > 0 {
+        println!("Excluded {} used answers.", excluded);
+    }
+    let wordlists: Vec<Rc<WordList>> = wordlists.into_iter().map(Rc::new).collect();
+    let mut game = Game::from_wordlist(wordlists[0].clone()).expect("failed to start game");
 
+    let mut profile = Profile::default();
     let mut guess_counter = Counter::new();
     let mut n_failed: usize = 0;
-    let allowed_solutions = game.wordlist.allowed_solutions.clone();
-    let n_total: usize = allowed_solutions.len();
+    let mut listed = opt.solution.clone();
+    if let Some(path) = &opt.solutions {
+        let list = fs::read_to_string(path).expect("failed to read solutions");
+        listed.extend(
+            list.lines()
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty()),
+        );
+    }
+    for s in &listed {
+        let n = s.chars().count();
+        if !wordlists.iter().any(|w| w.source.letter_count() == n) {
+            println!("Skipping {}: no {}-letter words", s, n);
+        }
+    }
+    let mut rng = match opt.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let mut groups: Vec<Group> = Vec::new();
+    for wordlist in &wordlists {
+        game.set_wordlist(wordlist.clone());
+        let letter_count = game.letter_count;
+        let solutions: Vec<String> = match opt.sample {
+            Some(n) => opt.prior.sample(wordlist, n, &mut rng),
+            None if listed.is_empty() => wordlist.allowed_solutions.iter().cloned().collect(),
+            None => listed
+                .iter()
+                .filter(|s| s.chars().count() == letter_count)
+                .filter_map(|s| match game.set_solution(s.clone()) {
+                    Ok(()) => Some(game.solution.clone()),
+                    Err(e) => {
+                        println!("Skipping {}: {}", s, e);
+                        None
+                    }
+                })
+                .collect(),
+        };
+        // partial samples are already drawn from the prior, full lists are weighted by it
+        let weights = match opt.sample {
+            Some(n) if n < wordlist.allowed_solutions.len() => vec![1.0; solutions.len()],
+            _ => opt.prior.weights(wordlist, &solutions),
+        };
+        let pool_size = new_solver(&mut game, &opt).guess_words().len();
+        groups.push(Group {
+            wordlist: wordlist.clone(),
+            solutions,
+            weights,
+            pool_size,
+        });
+    }
+    let weights: Vec<f64> = groups.iter().flat_map(|g| g.weights.clone()).collect();
 
-    let start = Instant::now();
-    for (i, solution) in allowed_solutions.iter().enumerate() {
-        game.set_solution(solution.to_string());
-        game.restart();
-        let mut solver: Solver = Solver::new(&mut game);
-        while let State::Unsolved = solver.game.state() {
-            solver.guess(&opt.mode);
-        }
-        match solver.game.state() {
-            State::Solved => {
-                let n = solver.game.guesses.len();
-                println!(
-                    "{}",
-                    game.guesses
-                        .iter()
-                        .map(|g| g.guess.to_string().to_string())
-                        .collect::<Vec<String>>()
-                        .join(",")
-                );
-                guess_counter.add(n);
+    let results = solve_groups(&mut game, &groups, &opt.mode, &opt, &mut profile, true);
+    let elapsed: Duration = results.iter().map(|r| r.elapsed).sum();
+    let runs: Vec<&Run> = results.iter().flat_map(|r| &r.runs).collect();
+    let n_total = runs.len();
+    let mut solved_mass = vec![0.0; game.tries + 1];
+    for (run, weight) in runs.iter().zip(&weights) {
+        if run.solved {
+            guess_counter.add(run.path.len());
+            solved_mass[run.path.len()] += weight;
+        } else {
+            n_failed += 1;
+        }
+    }
+    println!(
+        "Mode: {:?}, tie break: {:?}, prior: {:?}",
+        opt.mode, opt.tie_break, opt.prior
+    );
+    // the candidates pool shrinks during a game, its size is the one of the first turn, and
+    // differs between lengths
+    match &groups[..] {
+        [group] => println!(
+            "Guess pool: {:?} ({} words)",
+            opt.guess_pool, group.pool_size
+        ),
+        _ => println!("Guess pool: {:?}", opt.guess_pool),
+    }
+    if opt.profile {
+        print!("{}", profile);
+    }
+    if opt.worst > 0 {
+        let mut solved: Vec<&Run> = runs.iter().copied().filter(|r| r.solved).collect();
+        // most guesses first, then most candidates left along the way
+        solved.sort_by_key(|r| {
+            std::cmp::Reverse((r.path.len(), r.path.iter().map(|p| p.1).collect::<Vec<_>>()))
+        });
+        println!("Hardest solutions:");
+        for run in solved.iter().take(opt.worst) {
+            println!("  {}", run.describe());
+        }
+    }
+    let failed: Vec<&Run> = runs.iter().copied().filter(|r| !r.solved).collect();
+    if !failed.is_empty() {
+        println!("Failed solutions:");
+        for run in &failed {
+            println!("  {}", run.describe());
+        }
+        // group failures by their largest family among the possible solutions
+        let solutions: Vec<String> = wordlists
+            .iter()
+            .flat_map(|w| w.allowed_solutions.iter().cloned())
+            .collect();
+        let mut traps: Vec<(String, usize, Vec<String>)> = Vec::new();
+        for run in &failed {
+            let family = families::largest_family(&run.solution, &solutions);
+            if family.len() < 2 {
+                continue;
             }
-            _ => {
-                println!("{}/{} Failed to solve {}.", i + 1, n_total, solution);
-                n_failed += 1
+            match traps.iter_mut().find(|(k, _, _)| *k == family.key) {
+                Some((_, _, failures)) => failures.push(run.solution.clone()),
+                None => traps.push((family.key, family.members.len(), vec![run.solution.clone()])),
             }
         }
+        traps.sort_by(|a, b| b.2.len().cmp(&a.2.len()).then_with(|| b.1.cmp(&a.1)));
+        println!("Trap families:");
+        for (key, size, failures) in traps {
+            println!(
+                "  {} ({} solutions): {} failed: {}",
+                key,
+                size,
+                failures.len(),
+                failures.join(", ")
+            );
+        }
+    }
+    if results.len() > 1 {
+        println!(
+            "{:>7} {:>8} {:>8} {:>8} {:>7} {:>10}   first guess",
+            "length", "pool", "puzzles", "average", "failed", "ms/puzzle"
+        );
+        for (result, group) in results.iter().zip(&groups) {
+            let runs: Vec<&Run> = result.runs.iter().collect();
+            println!(
+                "{:>7} {:>8} {:>8} {:>8.2} {:>7} {:>10.2}   {}",
+                result.letter_count,
+                group.pool_size,
+                runs.len(),
+                average(&runs),
+                runs.iter().filter(|r| !r.solved).count(),
+                result.elapsed.as_millis() as f64 / runs.len().max(1) as f64,
+                result
+                    .opener
+                    .as_ref()
+                    .map(|w| w.to_string())
+                    .unwrap_or_default()
+            );
+        }
     }
-    let end = Instant::now();
     println!("Failed to solve {} puzzles.", n_failed);
-    let mut acc_n: usize = 0;
-    for (guesses, n) in guess_counter.as_vec() {
-        acc_n += n;
+    print!("{}", guess_counter.histogram(40, n_total));
+    let total_mass: f64 = weights.iter().sum();
+    let mut acc_mass = 0.0;
+    let within: Vec<String> = solved_mass
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(guesses, mass)| {
+            acc_mass += mass;
+            format!("{}: {:.3}", guesses, acc_mass / total_mass)
+        })
+        .collect();
+    println!("P(solved within N): {}", within.join(", "));
+    if let (Ok(mean), Ok(median), Ok(p90), Ok(std_dev), Ok(min), Ok(max)) = (
+        guess_counter.mean(),
+        guess_counter.median(),
+        guess_counter.percentile(90.0),
+        guess_counter.std_dev(),
+        guess_counter.min(),
+        guess_counter.max(),
+    ) {
         println!(
-            "{} guesses: {} ({:.1}% | {:.1}%)",
-            guesses,
-            n,
-            100.0 * (n as f64) / (n_total as f64),
-            100.0 * (acc_n as f64) / (n_total as f64)
+            "Guesses: median {}, 90th percentile {}, std dev {:.2}, min {}, max {}",
+            median, p90, std_dev, min, max
+        );
+        println!(
+            "Average: {:.2} guesses, {:.2} ms/puzzle",
+            mean,
+            elapsed.as_millis() as f64 / guess_counter.count() as f64,
         );
     }
-    println!(
-        "Average: {:.2} guesses, {:.2} ms/puzzle",
-        (guess_counter.sum() as f64) / (guess_counter.count() as f64),
-        ((end - start).as_millis() as f64 / guess_counter.count() as f64),
-    );
+    if let Some(baseline) = &opt.baseline {
+        let baseline_results = solve_groups(
+            &mut game,
+            &groups,
+            baseline,
+            &opt,
+            &mut Profile::default(),
+            false,
+        );
+        let baseline_elapsed: Duration = baseline_results.iter().map(|r| r.elapsed).sum();
+        let baseline_runs: Vec<&Run> = baseline_results.iter().flat_map(|r| &r.runs).collect();
+        let (mut more, mut fewer) = (0, 0);
+        for (run, base) in runs.iter().zip(&baseline_runs) {
+            match (run.solved, base.solved) {
+                (false, true) => more += 1,
+                (true, false) => fewer += 1,
+                (false, false) => {}
+                (true, true) if run.path.len() > base.path.len() => more += 1,
+                (true, true) if run.path.len() < base.path.len() => fewer += 1,
+                (true, true) => {}
+            }
+        }
+        let n = n_total.max(1) as f64;
+        println!(
+            "Baseline {:?}: {:.2} guesses ({:+.2}), {} failed, {:.2} ms/puzzle ({:.1}x the time)",
+            baseline,
+            average(&baseline_runs),
+            average(&runs) - average(&baseline_runs),
+            baseline_runs.iter().filter(|r| !r.solved).count(),
+            baseline_elapsed.as_millis() as f64 / n,
+            elapsed.as_secs_f64() / baseline_elapsed.as_secs_f64().max(f64::MIN_POSITIVE),
+        );
+        println!(
+            "Against the baseline: more guesses for {:.1}% of solutions, fewer for {:.1}%",
+            100.0 * more as f64 / n,
+            100.0 * fewer as f64 / n
+        );
+    }
+    // intervals only make sense for a random sample
+    if opt.sample.is_some() {
+        if let Ok((low, high)) = guess_counter.confidence_interval(1.96) {
+            println!("Average 95% confidence interval: {:.2} to {:.2}", low, high);
+        }
+        let (low, high) = wilson_interval(n_failed, n_total, 1.96);
+        println!(
+            "Failure rate: {:.2}% (95% confidence interval {:.2}% to {:.2}%)",
+            100.0 * n_failed as f64 / n_total.max(1) as f64,
+            100.0 * low,
+            100.0 * high
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_wilson_interval() {
+        let close =
+            |(a, b): (f64, f64), (c, d): (f64, f64)| (a - c).abs() < 1e-6 && (b - d).abs() < 1e-6;
+        assert!(close((0.0, 0.277_540), wilson_interval(0, 10, 1.96)));
+        assert!(close((0.722_460, 1.0), wilson_interval(10, 10, 1.96)));
+        assert!(close((0.236_590, 0.763_410), wilson_interval(5, 10, 1.96)));
+        assert_eq!((0.0, 1.0), wilson_interval(0, 0, 1.96));
+    }
 }
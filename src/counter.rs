@@ -1,20 +1,29 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
+use crate::error::Error;
+
+// Distribution of non-negative integer values, e.g. guesses per puzzle.
+#[derive(Debug, Clone)]
 pub struct Counter {
-    map: HashMap<usize, usize>,
+    map: BTreeMap<usize, usize>,
 }
 
 impl Counter {
     pub fn new() -> Self {
         Self {
-            map: HashMap::new(),
+            map: BTreeMap::new(),
         }
     }
     pub fn add(&mut self, count: usize) {
         *self.map.entry(count).or_insert(0) += 1;
     }
-    pub fn get(&mut self, count: usize) -> Option<usize> {
-        self.map.get(&count).map(|v| *v)
+    pub fn get(&self, count: usize) -> Option<usize> {
+        self.map.get(&count).copied()
+    }
+    pub fn merge(&mut self, other: &Counter) {
+        for (k, v) in &other.map {
+            *self.map.entry(*k).or_insert(0) += v;
+        }
     }
     pub fn sum(&self) -> usize {
         self.map.iter().map(|(k, v)| k * v).sum()
@@ -22,12 +31,137 @@ impl Counter {
     pub fn count(&self) -> usize {
         self.map.values().sum()
     }
-    pub fn max(&self) -> usize {
-        *self.map.keys().max().unwrap()
+    pub fn min(&self) -> Result<usize, Error> {
+        self.map.keys().next().copied().ok_or(Error::EmptyCounter)
+    }
+    pub fn max(&self) -> Result<usize, Error> {
+        self.map
+            .keys()
+            .next_back()
+            .copied()
+            .ok_or(Error::EmptyCounter)
+    }
+    pub fn mean(&self) -> Result<f64, Error> {
+        match self.count() {
+            0 => Err(Error::EmptyCounter),
+            n => Ok(self.sum() as f64 / n as f64),
+        }
+    }
+    // Population standard deviation.
+    pub fn std_dev(&self) -> Result<f64, Error> {
+        let mean = self.mean()?;
+        let squares: f64 = self
+            .map
+            .iter()
+            .map(|(k, v)| (*k as f64 - mean).powi(2) * *v as f64)
+            .sum();
+        Ok((squares / self.count() as f64).sqrt())
+    }
+    // Normal approximation interval for the mean, e.g. z = 1.96 for 95%, treating the values
+    // as a random sample.
+    pub fn confidence_interval(&self, z: f64) -> Result<(f64, f64), Error> {
+        let mean = self.mean()?;
+        let n = self.count() as f64;
+        if n < 2.0 {
+            return Ok((mean, mean));
+        }
+        let sample_std_dev = self.std_dev()? * (n / (n - 1.0)).sqrt();
+        let margin = z * sample_std_dev / n.sqrt();
+        Ok((mean - margin, mean + margin))
+    }
+    // Smallest value with at least `p` percent of the values at or below it.
+    pub fn percentile(&self, p: f64) -> Result<usize, Error> {
+        let rank = ((p / 100.0) * self.count() as f64).ceil().max(1.0) as usize;
+        let mut seen = 0;
+        for (k, v) in &self.map {
+            seen += v;
+            if seen >= rank {
+                return Ok(*k);
+            }
+        }
+        self.max()
+    }
+    pub fn median(&self) -> Result<usize, Error> {
+        self.percentile(50.0)
     }
     pub fn as_vec(&self) -> Vec<(usize, usize)> {
-        let mut vec: Vec<(usize, usize)> = self.map.iter().map(|(a, b)| (*a, *b)).collect();
-        vec.sort_by_key(|t| t.0);
-        vec
+        self.map.iter().map(|(a, b)| (*a, *b)).collect()
+    }
+    // One line per value: a bar of up to `width` characters, the count, and its share and
+    // cumulative share of `total`, which may include outcomes not counted, e.g. failures.
+    pub fn histogram(&self, width: usize, total: usize) -> String {
+        let total = total.max(1) as f64;
+        let largest = self.map.values().max().copied().unwrap_or(0).max(1);
+        let mut acc = 0;
+        let mut out = String::new();
+        for (k, v) in &self.map {
+            acc += v;
+            let bar = "#".repeat((v * width).div_ceil(largest));
+            out.push_str(&format!(
+                "{:>3} | {:<width$} {} ({:.1}% | {:.1}%)\n",
+                k,
+                bar,
+                v,
+                100.0 * *v as f64 / total,
+                100.0 * acc as f64 / total,
+                width = width,
+            ));
+        }
+        out
+    }
+}
+
+impl Default for Counter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn counter(values: &[usize]) -> Counter {
+        let mut counter = Counter::new();
+        for v in values {
+            counter.add(*v);
+        }
+        counter
+    }
+
+    #[test]
+    fn test_statistics() {
+        let c = counter(&[3, 4, 3, 2, 4, 4, 6, 3]);
+        assert_eq!(Some(3), c.get(3));
+        assert_eq!(None, c.get(5));
+        assert_eq!(2, c.min().unwrap());
+        assert_eq!(6, c.max().unwrap());
+        assert!((c.mean().unwrap() - 3.625).abs() < 1e-9);
+        assert!((c.std_dev().unwrap() - 1.111_024).abs() < 1e-6);
+        assert_eq!(3, c.median().unwrap());
+        assert_eq!(4, c.percentile(75.0).unwrap());
+        assert_eq!(6, c.percentile(100.0).unwrap());
+        assert_eq!(2, c.percentile(0.0).unwrap());
+        let (low, high) = c.confidence_interval(1.96).unwrap();
+        assert!((high - low - 2.0 * 1.96 * 1.187_735 / 8f64.sqrt()).abs() < 1e-5);
+        assert!(((low + high) / 2.0 - 3.625).abs() < 1e-9);
+        assert!(matches!(Counter::new().mean(), Err(Error::EmptyCounter)));
+        assert!(matches!(Counter::new().median(), Err(Error::EmptyCounter)));
+    }
+
+    #[test]
+    fn test_merge_and_histogram() {
+        let mut c = counter(&[3, 3, 4]);
+        c.merge(&counter(&[4, 4, 5]));
+        assert_eq!(vec![(3, 2), (4, 3), (5, 1)], c.as_vec());
+        assert_eq!(
+            "  3 | ####   2 (33.3% | 33.3%)\n  4 | ###### 3 (50.0% | 83.3%)\n  5 | ##     1 (16.7% | 100.0%)\n",
+            c.histogram(6, c.count())
+        );
+        // two more runs failed
+        assert_eq!(
+            "  3 | ####   2 (25.0% | 25.0%)\n  4 | ###### 3 (37.5% | 62.5%)\n  5 | ##     1 (12.5% | 75.0%)\n",
+            c.histogram(6, 8)
+        );
     }
 }
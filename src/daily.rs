@@ -0,0 +1,158 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::Error;
+
+// Wordle #0 was published on this date.
+pub const FIRST_PUZZLE: Date = Date {
+    year: 2021,
+    month: 6,
+    day: 19,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn new(year: i64, month: u32, day: u32) -> Result<Self, Error> {
+        let date = Self { year, month, day };
+        // round trip through day count to reject e.g. 2022-02-30
+        if month == 0 || day == 0 || Self::from_days(date.days()) != date {
+            return Err(Error::InvalidDate(format!("{}-{}-{}", year, month, day)));
+        }
+        Ok(date)
+    }
+    // Current UTC date.
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self::from_days((secs / 86400) as i64)
+    }
+    // Days since 1970-01-01, from Howard Hinnant's civil calendar algorithms.
+    pub fn days(&self) -> i64 {
+        let y = if self.month <= 2 {
+            self.year - 1
+        } else {
+            self.year
+        };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = self.month as i64;
+        let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+    pub fn from_days(days: i64) -> Self {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        Self { year, month, day }
+    }
+    pub fn puzzle_number(&self) -> Result<usize, Error> {
+        let n = self.days() - FIRST_PUZZLE.days();
+        if n < 0 {
+            return Err(Error::InvalidDate(self.to_string()));
+        }
+        Ok(n as usize)
+    }
+    pub fn from_puzzle_number(n: usize) -> Self {
+        Self::from_days(FIRST_PUZZLE.days() + n as i64)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let parts: Vec<&str> = s.split('-').collect();
+        if parts.len() != 3 {
+            return Err("date must be YYYY-MM-DD".to_string());
+        }
+        let year = parts[0].parse().map_err(|e| format!("{}", e))?;
+        let month = parts[1].parse().map_err(|e| format!("{}", e))?;
+        let day = parts[2].parse().map_err(|e| format!("{}", e))?;
+        Self::new(year, month, day).map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum Puzzle {
+    Today,
+    Date(Date),
+    Number(usize),
+}
+
+impl Puzzle {
+    pub fn number(&self) -> Result<usize, Error> {
+        match self {
+            Self::Today => Date::today().puzzle_number(),
+            Self::Date(date) => date.puzzle_number(),
+            Self::Number(n) => Ok(*n),
+        }
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        if s == "today" {
+            return Ok(Self::Today);
+        }
+        match s.parse::<usize>() {
+            Ok(n) => Ok(Self::Number(n)),
+            Err(_) => Ok(Self::Date(s.parse()?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_days_round_trip() {
+        assert_eq!(0, Date::new(1970, 1, 1).unwrap().days());
+        for days in -1000..100_000 {
+            assert_eq!(days, Date::from_days(days).days());
+        }
+        assert!(Date::new(2022, 2, 29).is_err());
+        assert!(Date::new(2024, 2, 29).is_ok());
+        assert!("2022-13-01".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn test_puzzle_number() {
+        assert_eq!(0, FIRST_PUZZLE.puzzle_number().unwrap());
+        let date: Date = "2022-01-01".parse().unwrap();
+        assert_eq!(196, date.puzzle_number().unwrap());
+        assert_eq!(date, Date::from_puzzle_number(196));
+        assert!("2021-06-18"
+            .parse::<Date>()
+            .unwrap()
+            .puzzle_number()
+            .is_err());
+        match "2022-01-01".parse::<Puzzle>().unwrap() {
+            Puzzle::Date(d) => assert_eq!(date, d),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(42, "42".parse::<Puzzle>().unwrap().number().unwrap());
+    }
+}
@@ -0,0 +1,61 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+use crate::game::State;
+use crate::InvalidLetter;
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidLetter(InvalidLetter),
+    InvalidGuess(String),
+    InvalidSolution(String),
+    InvalidFeedback(String),
+    InvalidPuzzle(usize),
+    InvalidDate(String),
+    GameFinished(State),
+    NoSolutions,
+    EmptyCounter,
+    BadWordList(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLetter(e) => write!(fmt, "{}", e),
+            Self::InvalidGuess(guess) => write!(fmt, "invalid guess {:?}", guess),
+            Self::InvalidSolution(solution) => write!(fmt, "invalid solution {:?}", solution),
+            Self::InvalidFeedback(feedback) => write!(fmt, "invalid feedback {:?}", feedback),
+            Self::InvalidPuzzle(n) => write!(fmt, "no answer for puzzle #{}", n),
+            Self::InvalidDate(date) => write!(fmt, "invalid date {}", date),
+            Self::GameFinished(state) => write!(fmt, "game is finished ({:?})", state),
+            Self::NoSolutions => write!(fmt, "no possible solutions left"),
+            Self::EmptyCounter => write!(fmt, "counter is empty"),
+            Self::BadWordList(reason) => write!(fmt, "bad word list: {}", reason),
+            Self::Io(e) => write!(fmt, "{}", e),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::InvalidLetter(e) => Some(e),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<InvalidLetter> for Error {
+    fn from(e: InvalidLetter) -> Self {
+        Self::InvalidLetter(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
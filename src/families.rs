@@ -0,0 +1,176 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::alphabet::Alphabet;
+use crate::feedback::feedback;
+use crate::Word;
+
+// Word families are words equal but for one position, written like "_ight".
+pub const WILDCARD: char = '_';
+
+pub fn family_key(word: &str, position: usize) -> String {
+    word.chars()
+        .enumerate()
+        .map(|(i, c)| if i == position { WILDCARD } else { c })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Family {
+    pub key: String,
+    pub position: usize,
+    pub members: Vec<String>,
+}
+
+impl Family {
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+    // The letters telling the members apart.
+    pub fn letters(&self) -> Vec<char> {
+        self.members
+            .iter()
+            .filter_map(|m| m.chars().nth(self.position))
+            .collect()
+    }
+    // Lower bound on the guesses telling the members apart: a guess tests at most one letter
+    // per position and the last member needs no test. The actual minimum over a guess pool
+    // may be higher; `splitters` gives an upper bound.
+    pub fn guesses_lower_bound(&self) -> usize {
+        let letter_count = self.key.chars().count().max(1);
+        self.len().saturating_sub(1).div_ceil(letter_count)
+    }
+    // Greedily picks up to `max_guesses` guesses, each splitting the members still sharing a
+    // feedback into the most groups, until every member gets a distinct feedback.
+    pub fn splitters(
+        &self,
+        alphabet: &Alphabet,
+        guesses: &[Word],
+        max_guesses: usize,
+    ) -> Vec<Word> {
+        let members: Vec<Word> = self
+            .members
+            .iter()
+            .filter_map(|m| alphabet.word(m).ok())
+            .collect();
+        // group of each member, by the feedback of the splitters so far
+        let mut groups: Vec<usize> = vec![0; members.len()];
+        let mut n_groups = 1;
+        let mut splitters = Vec::new();
+        while n_groups < members.len() && splitters.len() < max_guesses {
+            let mut best: Option<(usize, &Word)> = None;
+            for guess in guesses {
+                let refined: HashSet<_> = members
+                    .iter()
+                    .zip(&groups)
+                    .map(|(m, g)| (*g, feedback(guess, m)))
+                    .collect();
+                if refined.len() > best.map_or(n_groups, |b| b.0) {
+                    best = Some((refined.len(), guess));
+                }
+            }
+            let (count, guess) = match best {
+                Some(best) => best,
+                None => break,
+            };
+            let mut ids = HashMap::new();
+            for (m, g) in members.iter().zip(groups.iter_mut()) {
+                let next = ids.len();
+                *g = *ids.entry((*g, feedback(guess, m))).or_insert(next);
+            }
+            n_groups = count;
+            splitters.push(guess.clone());
+        }
+        splitters
+    }
+}
+
+// Families of the given words with at least `min_size` members, largest first.
+pub fn families(words: &[String], min_size: usize) -> Vec<Family> {
+    let mut by_key: HashMap<(String, usize), Vec<String>> = HashMap::new();
+    for word in words {
+        for position in 0..word.chars().count() {
+            by_key
+                .entry((family_key(word, position), position))
+                .or_default()
+                .push(word.to_string());
+        }
+    }
+    let mut families: Vec<Family> = by_key
+        .into_iter()
+        .filter(|(_, members)| members.len() >= min_size)
+        .map(|((key, position), members)| Family {
+            key,
+            position,
+            members,
+        })
+        .collect();
+    families.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.key.cmp(&b.key)));
+    families
+}
+
+// Largest family of `word` among `words`.
+pub fn largest_family(word: &str, words: &[String]) -> Family {
+    (0..word.chars().count())
+        .map(|position| {
+            let key = family_key(word, position);
+            let members: Vec<String> = words
+                .iter()
+                .filter(|w| family_key(w, position) == key)
+                .cloned()
+                .collect();
+            Family {
+                key,
+                position,
+                members,
+            }
+        })
+        .max_by_key(|family| family.len())
+        .unwrap_or_else(|| Family {
+            key: word.to_string(),
+            position: 0,
+            members: vec![word.to_string()],
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_families() {
+        let list = words(&["fight", "light", "might", "match", "latch", "mince"]);
+        assert_eq!("_ight", family_key("light", 0));
+        let family = largest_family("light", &list);
+        assert_eq!("_ight", family.key);
+        assert_eq!(words(&["fight", "light", "might"]), family.members);
+        assert_eq!(vec!['f', 'l', 'm'], family.letters());
+        let families = families(&list, 2);
+        assert_eq!(2, families.len());
+        assert_eq!("_ight", families[0].key);
+        assert_eq!(words(&["match", "latch"]), families[1].members);
+    }
+
+    #[test]
+    fn test_splitters() {
+        let alphabet = Alphabet::english();
+        let list = words(&[
+            "fight", "light", "might", "night", "right", "sight", "tight",
+        ]);
+        let family = largest_family("light", &list);
+        assert_eq!(2, family.guesses_lower_bound());
+        let guesses: Vec<Word> = ["hello", "flint", "moths", "grasp"]
+            .iter()
+            .map(|g| alphabet.word(g).unwrap())
+            .collect();
+        let splitters = family.splitters(&alphabet, &guesses, 3);
+        let splitters: Vec<String> = splitters.iter().map(String::from).collect();
+        assert_eq!(words(&["flint", "moths", "grasp"]), splitters);
+    }
+}
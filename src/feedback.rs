@@ -0,0 +1,247 @@
+use crate::game::{GuessResult, LetterMatch};
+use crate::Word;
+
+// Words are packed one letter per 6-bit lane, storing letter index + 1 so that
+// an empty lane never compares equal to a letter.
+const LANE_BITS: usize = 6;
+pub const MAX_PACKED_LEN: usize = 128 / LANE_BITS;
+
+const fn repeat_lane(value: u128) -> u128 {
+    let mut acc = 0;
+    let mut i = 0;
+    while i < MAX_PACKED_LEN {
+        acc |= value << (i * LANE_BITS);
+        i += 1;
+    }
+    acc
+}
+
+const LANE_MASK: u128 = (1 << LANE_BITS) - 1;
+const LANE_ONES: u128 = repeat_lane(1);
+const LANE_LOW: u128 = repeat_lane(LANE_MASK >> 1);
+const LANE_HIGH: u128 = repeat_lane(1 << (LANE_BITS - 1));
+
+const MATCH_BITS: usize = 2;
+const MATCH_WRONG: u64 = 0;
+const MATCH_PARTIAL: u64 = 1;
+const MATCH_EXACT: u64 = 2;
+
+// High bit of each lane is set iff the lane is zero.
+fn zero_lanes(x: u128) -> u128 {
+    !(((x & LANE_LOW) + LANE_LOW) | x | LANE_LOW) & LANE_HIGH
+}
+
+fn high_bits(len: usize) -> u128 {
+    LANE_HIGH & ((1 << (len * LANE_BITS)) - 1)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PackedWord {
+    bits: u128,
+    len: usize,
+}
+
+impl PackedWord {
+    pub fn new(word: &Word) -> Option<Self> {
+        if word.len() > MAX_PACKED_LEN {
+            return None;
+        }
+        let mut bits: u128 = 0;
+        for (i, letter) in word.vec.iter().enumerate() {
+            let lane = letter.as_index() as u128 + 1;
+            if lane > LANE_MASK {
+                return None;
+            }
+            bits |= lane << (i * LANE_BITS);
+        }
+        Some(Self {
+            bits,
+            len: word.len(),
+        })
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    fn lane(&self, i: usize) -> u128 {
+        (self.bits >> (i * LANE_BITS)) & LANE_MASK
+    }
+}
+
+// Feedback for one guess, two bits per letter position.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pattern(u64);
+
+impl Pattern {
+    pub fn solved(len: usize) -> Self {
+        let mut pattern = Self(0);
+        for i in 0..len {
+            pattern.set(i, MATCH_EXACT);
+        }
+        pattern
+    }
+    pub fn from_matches(matches: &[LetterMatch]) -> Self {
+        let mut pattern = Self(0);
+        for (i, m) in matches.iter().enumerate() {
+            pattern.set(
+                i,
+                match m {
+                    LetterMatch::Exact => MATCH_EXACT,
+                    LetterMatch::Partial => MATCH_PARTIAL,
+                    LetterMatch::Wrong => MATCH_WRONG,
+                },
+            );
+        }
+        pattern
+    }
+    pub fn to_matches(self, len: usize) -> Vec<LetterMatch> {
+        (0..len)
+            .map(|i| match (self.0 >> (i * MATCH_BITS)) & 0b11 {
+                MATCH_EXACT => LetterMatch::Exact,
+                MATCH_PARTIAL => LetterMatch::Partial,
+                _ => LetterMatch::Wrong,
+            })
+            .collect()
+    }
+    pub fn is_solved(self, len: usize) -> bool {
+        self == Self::solved(len)
+    }
+    fn set(&mut self, i: usize, m: u64) {
+        self.0 |= m << (i * MATCH_BITS);
+    }
+}
+
+impl From<&GuessResult> for Pattern {
+    fn from(gr: &GuessResult) -> Self {
+        Self::from_matches(&gr.result)
+    }
+}
+
+pub fn feedback_packed(guess: &PackedWord, solution: &PackedWord) -> Pattern {
+    let len = guess.len;
+    let exact = zero_lanes(guess.bits ^ solution.bits) & high_bits(len);
+    let mut unmatched = high_bits(len) & !exact;
+    let mut pattern = Pattern(0);
+    for i in 0..len {
+        let lane_high = 1 << (i * LANE_BITS + LANE_BITS - 1);
+        if exact & lane_high != 0 {
+            pattern.set(i, MATCH_EXACT);
+        } else if unmatched != 0 {
+            let hits = zero_lanes(solution.bits ^ (guess.lane(i) * LANE_ONES)) & unmatched;
+            if hits != 0 {
+                // consume the leftmost unmatched occurrence in the solution
+                unmatched ^= hits & hits.wrapping_neg();
+                pattern.set(i, MATCH_PARTIAL);
+            }
+        }
+    }
+    pattern
+}
+
+pub fn feedback_scalar(guess: &Word, solution: &Word) -> Pattern {
+    (&GuessResult::check(guess, solution, guess.len())).into()
+}
+
+pub fn feedback(guess: &Word, solution: &Word) -> Pattern {
+    match (PackedWord::new(guess), PackedWord::new(solution)) {
+        (Some(g), Some(s)) => feedback_packed(&g, &s),
+        _ => feedback_scalar(guess, solution),
+    }
+}
+
+pub fn feedback_batch(guess: &PackedWord, solutions: &[PackedWord], out: &mut Vec<Pattern>) {
+    out.clear();
+    out.extend(solutions.iter().map(|s| feedback_packed(guess, s)));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::alphabet::{Alphabet, LETTERS};
+    use rand::{thread_rng, Rng};
+
+    fn random_word(rng: &mut impl Rng, len: usize, letters: &str) -> Word {
+        let letters: Vec<char> = letters.chars().collect();
+        let s: String = (0..len)
+            .map(|_| letters[rng.gen_range(0..letters.len())])
+            .collect();
+        let all: String = LETTERS.iter().collect();
+        Alphabet::new(&all, false).unwrap().word(&s).unwrap()
+    }
+
+    fn assert_same(guess: &Word, solution: &Word) {
+        let expected = GuessResult::check(guess, solution, guess.len());
+        let actual = feedback(guess, solution);
+        assert_eq!(
+            Pattern::from(&expected),
+            actual,
+            "{} vs {}",
+            guess,
+            solution
+        );
+        assert_eq!(expected.result, actual.to_matches(guess.len()));
+    }
+
+    #[test]
+    fn test_feedback_repeated_letters() {
+        for (guess, solution) in &[
+            ("relax", "relax"),
+            ("relax", "bbbbb"),
+            ("accca", "aaabb"),
+            ("acaaa", "aabbb"),
+            ("speed", "abide"),
+            ("eerie", "where"),
+        ] {
+            assert_same(&guess.parse().unwrap(), &solution.parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_feedback_random_pairs() {
+        let mut rng = thread_rng();
+        // small alphabets force lots of repeated letters
+        for letters in &[
+            "ab",
+            "abc",
+            "aeiost",
+            "abcdefghijklmnopqrstuvwxyz",
+            "ñßÿüeé",
+        ] {
+            for len in 1..=MAX_PACKED_LEN {
+                for _ in 0..200 {
+                    let guess = random_word(&mut rng, len, letters);
+                    let solution = random_word(&mut rng, len, letters);
+                    assert_same(&guess, &solution);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_feedback_batch() {
+        let mut rng = thread_rng();
+        let guess = random_word(&mut rng, 5, "abcde");
+        let solutions: Vec<Word> = (0..100)
+            .map(|_| random_word(&mut rng, 5, "abcde"))
+            .collect();
+        let packed: Vec<PackedWord> = solutions
+            .iter()
+            .map(|w| PackedWord::new(w).unwrap())
+            .collect();
+        let mut out = vec![];
+        feedback_batch(&PackedWord::new(&guess).unwrap(), &packed, &mut out);
+        for (solution, pattern) in solutions.iter().zip(out) {
+            assert_eq!(feedback_scalar(&guess, solution), pattern);
+        }
+    }
+
+    #[test]
+    fn test_feedback_too_long_falls_back() {
+        let guess: Word = "abcdefghijklmnopqrstuvwxyz".parse().unwrap();
+        let solution: Word = "zyxwvutsrqponmlkjihgfedcba".parse().unwrap();
+        assert!(PackedWord::new(&guess).is_none());
+        assert_same(&guess, &solution);
+    }
+}
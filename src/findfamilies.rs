@@ -0,0 +1,70 @@
+use structopt::StructOpt;
+
+use wordsmith::families;
+use wordsmith::words::{WordList, WordSource};
+use wordsmith::Word;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "ws-families")]
+struct Opt {
+    #[structopt(short, long, default_value = "wordle")]
+    word_source: WordSource,
+    // smallest family to report
+    #[structopt(long, default_value = "3")]
+    min_size: usize,
+    // report the N largest families, 0 for all
+    #[structopt(long, default_value = "20")]
+    top: usize,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let wordlist = WordList::from_source(&opt.word_source).expect("failed to load word list");
+    let mut solutions: Vec<String> = wordlist.allowed_solutions.iter().cloned().collect();
+    solutions.sort();
+    let guesses: Vec<Word> = wordlist
+        .words_by_frequency
+        .iter()
+        .map(|w| wordlist.alphabet.word(w).unwrap())
+        .collect();
+
+    let families = families::families(&solutions, opt.min_size);
+    let in_families: usize = {
+        let mut members: Vec<&String> = families.iter().flat_map(|f| &f.members).collect();
+        members.sort();
+        members.dedup();
+        members.len()
+    };
+    println!(
+        "{} families of at least {} solutions, covering {} of {} solutions",
+        families.len(),
+        opt.min_size,
+        in_families,
+        solutions.len()
+    );
+    println!(
+        "{:<12} {:>5} {:>5} {:>7}   splitters",
+        "family", "size", "lower", "greedy"
+    );
+    let top = if opt.top == 0 {
+        families.len()
+    } else {
+        opt.top
+    };
+    for family in families.iter().take(top) {
+        let splitters: Vec<String> = family
+            .splitters(&wordlist.alphabet, &guesses, family.len())
+            .iter()
+            .map(String::from)
+            .collect();
+        println!(
+            "{:<12} {:>5} {:>5} {:>7}   {} ({})",
+            family.key,
+            family.len(),
+            family.guesses_lower_bound(),
+            splitters.len(),
+            splitters.join(", "),
+            family.letters().into_iter().collect::<String>()
+        );
+    }
+}
@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 use std::rc::Rc;
 
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::SeedableRng;
 
+use crate::alphabet::MAX_LETTERS;
+use crate::error::Error;
 use crate::words::{WordList, WordSource};
 use crate::{Letter, Word};
 
@@ -14,36 +17,82 @@ pub struct Game {
     pub solution: String,
     pub wordlist: Rc<WordList>,
     pub guesses: Vec<GuessResult>,
+    rng: StdRng,
 }
 
 impl Game {
-    fn new(letter_count: usize, wordlist: Rc<WordList>) -> Self {
-        let solution = wordlist
-            .allowed_solutions
-            .iter()
-            .collect::<Vec<&String>>()
-            .choose(&mut thread_rng())
-            .unwrap()
-            .to_string();
-        Self {
+    fn new(letter_count: usize, wordlist: Rc<WordList>, rng: StdRng) -> Result<Self, Error> {
+        let mut game = Self {
             letter_count,
             wordlist,
             guesses: vec![],
             tries: 6,
-            solution,
-        }
+            solution: String::new(),
+            rng,
+        };
+        game.pick_solution()?;
+        Ok(game)
     }
-    pub fn from_source(source: &WordSource) -> Self {
+    pub fn from_source(source: &WordSource) -> Result<Self, Error> {
+        Self::from_wordlist(Rc::new(WordList::from_source(source)?))
+    }
+    pub fn from_wordlist(wordlist: Rc<WordList>) -> Result<Self, Error> {
         Self::new(
-            source.letter_count(),
-            Rc::new(WordList::from_source(source)),
+            wordlist.source.letter_count(),
+            wordlist,
+            StdRng::from_entropy(),
         )
     }
-    pub fn set_solution(&mut self, solution: String) {
+    // Same seed, same sequence of solutions from pick_solution.
+    pub fn from_wordlist_seeded(wordlist: Rc<WordList>, seed: u64) -> Result<Self, Error> {
+        Self::new(
+            wordlist.source.letter_count(),
+            wordlist,
+            StdRng::seed_from_u64(seed),
+        )
+    }
+    pub fn pick_solution(&mut self) -> Result<(), Error> {
+        // answers in their fixed order, for seeded games, less the excluded ones
+        let answers: Vec<&String> = self
+            .wordlist
+            .answers
+            .iter()
+            .filter(|a| self.wordlist.is_valid_solution(a))
+            .collect();
+        self.solution = answers
+            .choose(&mut self.rng)
+            .ok_or(Error::NoSolutions)?
+            .to_string();
+        Ok(())
+    }
+    // Switches to another word list, e.g. of another length, for the next game.
+    pub fn set_wordlist(&mut self, wordlist: Rc<WordList>) {
+        self.letter_count = wordlist.source.letter_count();
+        self.wordlist = wordlist;
+        self.restart();
+    }
+    // Picks the word list, and so the length, of the next game, then its solution.
+    pub fn pick_wordlist(&mut self, wordlists: &[Rc<WordList>]) -> Result<(), Error> {
+        let wordlist = wordlists.choose(&mut self.rng).ok_or(Error::NoSolutions)?;
+        self.set_wordlist(wordlist.clone());
+        self.pick_solution()
+    }
+    pub fn set_puzzle(&mut self, number: usize) -> Result<(), Error> {
+        match self.wordlist.puzzle(number) {
+            Some(solution) => {
+                self.solution = solution.to_string();
+                Ok(())
+            }
+            None => Err(Error::InvalidPuzzle(number)),
+        }
+    }
+    pub fn set_solution(&mut self, solution: String) -> Result<(), Error> {
+        let solution = self.wordlist.alphabet.word(&solution)?.to_string();
         if !self.wordlist.is_valid_solution(&solution) {
-            panic!("invalid solution");
+            return Err(Error::InvalidSolution(solution));
         }
         self.solution = solution;
+        Ok(())
     }
     pub fn restart(&mut self) {
         self.guesses = vec![];
@@ -79,19 +128,33 @@ impl Game {
         }
         map
     }
-    pub fn guess(&mut self, guess: String) -> Result<GuessResult, GuessError> {
-        if !self.wordlist.is_valid_guess(&guess) {
-            return Err(GuessError::InvalidGuess(guess));
+    pub fn guess(&mut self, guess: String) -> Result<GuessResult, Error> {
+        let guess_word: Word = self.wordlist.alphabet.word(&guess)?;
+        if !self.wordlist.is_valid_guess(&guess_word.to_string()) {
+            return Err(Error::InvalidGuess(guess));
         }
         match &self.state() {
             State::Unsolved => {
-                let result =
-                    GuessResult::check(&(&guess[..]).into(), &self.solution, self.letter_count);
+                let solution_word = self.wordlist.alphabet.word(&self.solution)?;
+                let result = GuessResult::check(&guess_word, &solution_word, self.letter_count);
                 self.guesses.push(result.clone());
                 Ok(result)
             }
-            State::Solved => Err(GuessError::GameFinished(State::Solved)),
-            State::Failed => Err(GuessError::GameFinished(State::Failed)),
+            State::Solved => Err(Error::GameFinished(State::Solved)),
+            State::Failed => Err(Error::GameFinished(State::Failed)),
+        }
+    }
+    // Record feedback computed elsewhere, when the solution is not known.
+    pub fn record(&mut self, result: GuessResult) -> Result<(), Error> {
+        if result.guess.len() != self.letter_count {
+            return Err(Error::InvalidGuess(result.guess.to_string()));
+        }
+        match self.state() {
+            State::Unsolved => {
+                self.guesses.push(result);
+                Ok(())
+            }
+            state => Err(Error::GameFinished(state)),
         }
     }
     pub fn letter_count(&self) -> usize {
@@ -113,6 +176,24 @@ pub enum LetterMatch {
     Wrong,
 }
 
+impl LetterMatch {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_lowercase() {
+            'g' => Some(Self::Exact),
+            'y' => Some(Self::Partial),
+            'b' => Some(Self::Wrong),
+            _ => None,
+        }
+    }
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Exact => 'g',
+            Self::Partial => 'y',
+            Self::Wrong => 'b',
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct GuessResult {
     pub guess: Word,
@@ -120,6 +201,19 @@ pub struct GuessResult {
 }
 
 impl GuessResult {
+    pub fn from_feedback(guess: Word, feedback: &str) -> Result<Self, Error> {
+        let result = feedback
+            .chars()
+            .map(LetterMatch::from_char)
+            .collect::<Option<Vec<LetterMatch>>>();
+        match result {
+            Some(result) if result.len() == guess.len() => Ok(Self { guess, result }),
+            _ => Err(Error::InvalidFeedback(feedback.to_string())),
+        }
+    }
+    pub fn feedback(&self) -> String {
+        self.result.iter().map(|m| m.as_char()).collect()
+    }
     pub fn is_solved(&self) -> bool {
         for m in &self.result {
             match m {
@@ -131,13 +225,11 @@ impl GuessResult {
         }
         true
     }
-    pub fn check(guess_word: &Word, solution: &str, letter_count: usize) -> Self {
+    pub fn check(guess_word: &Word, solution_word: &Word, letter_count: usize) -> Self {
         let mut res: Vec<LetterMatch> = vec![];
 
-        let solution_word: Word = solution.into();
-
-        let mut count_nonexact_guess: [u8; 26] = [0; 26];
-        let mut count_nonexact_solution: [u8; 26] = [0; 26];
+        let mut count_nonexact_guess: [u8; MAX_LETTERS] = [0; MAX_LETTERS];
+        let mut count_nonexact_solution: [u8; MAX_LETTERS] = [0; MAX_LETTERS];
 
         for i in 0..letter_count {
             if guess_word[i] != solution_word[i] {
@@ -166,12 +258,7 @@ impl GuessResult {
     }
 }
 
-#[derive(Clone, Debug)]
-pub enum GuessError {
-    InvalidGuess(String),
-    GameFinished(State),
-}
-
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum LetterState {
     Exact,
     Partial,
@@ -182,13 +269,18 @@ pub enum LetterState {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::InvalidLetter;
     use LetterMatch::*;
 
+    fn w(s: &str) -> Word {
+        s.parse().unwrap()
+    }
+
     #[test]
     fn test_guess_check_solved() {
-        let actual = GuessResult::check(&"relax".into(), "relax", 5);
+        let actual = GuessResult::check(&w("relax"), &w("relax"), 5);
         let expected = GuessResult {
-            guess: "relax".into(),
+            guess: w("relax"),
             result: vec![Exact, Exact, Exact, Exact, Exact],
         };
         assert_eq!(expected, actual);
@@ -197,9 +289,9 @@ mod test {
 
     #[test]
     fn test_guess_check_wrong() {
-        let actual = GuessResult::check(&"relax".into(), "bbbbb", 5);
+        let actual = GuessResult::check(&w("relax"), &w("bbbbb"), 5);
         let expected = GuessResult {
-            guess: "relax".into(),
+            guess: w("relax"),
             result: vec![Wrong, Wrong, Wrong, Wrong, Wrong],
         };
         assert_eq!(expected, actual);
@@ -208,9 +300,9 @@ mod test {
 
     #[test]
     fn test_guess_check_partial_1() {
-        let actual = GuessResult::check(&"accca".into(), "aaabb", 5);
+        let actual = GuessResult::check(&w("accca"), &w("aaabb"), 5);
         let expected = GuessResult {
-            guess: "accca".into(),
+            guess: w("accca"),
             result: vec![Exact, Wrong, Wrong, Wrong, Partial],
         };
         assert_eq!(expected, actual);
@@ -219,12 +311,74 @@ mod test {
 
     #[test]
     fn test_guess_check_partial_2() {
-        let actual = GuessResult::check(&"acaaa".into(), "aabbb", 5);
+        let actual = GuessResult::check(&w("acaaa"), &w("aabbb"), 5);
         let expected = GuessResult {
-            guess: "acaaa".into(),
+            guess: w("acaaa"),
             result: vec![Exact, Wrong, Partial, Wrong, Wrong],
         };
         assert_eq!(expected, actual);
         assert!(!actual.is_solved());
     }
+
+    #[test]
+    fn test_guess_normalizes_case() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("RELAX".to_string()).unwrap();
+        let result = game.guess("Relax".to_string()).unwrap();
+        assert_eq!(w("relax"), result.guess);
+        assert!(result.is_solved());
+    }
+
+    #[test]
+    fn test_guess_invalid_letter() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        match game.guess("rélax".to_string()) {
+            Err(Error::InvalidLetter(InvalidLetter(c))) => assert_eq!('é', c),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(game.guesses.is_empty());
+    }
+
+    #[test]
+    fn test_set_invalid_solution() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        match game.set_solution("aahed".to_string()) {
+            Err(Error::InvalidSolution(s)) => assert_eq!("aahed", s),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_feedback_round_trip() {
+        let actual = GuessResult::check(&w("accca"), &w("aaabb"), 5);
+        assert_eq!("gbbby", actual.feedback());
+        assert_eq!(
+            actual,
+            GuessResult::from_feedback(w("accca"), "GBBBY").unwrap()
+        );
+        assert!(GuessResult::from_feedback(w("accca"), "gbbb").is_err());
+        assert!(GuessResult::from_feedback(w("accca"), "gbbbx").is_err());
+    }
+
+    #[test]
+    fn test_seeded_solutions() {
+        let wordlist = Rc::new(WordList::from_source(&WordSource::Wordle).unwrap());
+        let mut a = Game::from_wordlist_seeded(wordlist.clone(), 42).unwrap();
+        let mut b = Game::from_wordlist_seeded(wordlist, 42).unwrap();
+        for _ in 0..10 {
+            assert_eq!(a.solution, b.solution);
+            a.pick_solution().unwrap();
+            b.pick_solution().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_daily_puzzle() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_puzzle(0).unwrap();
+        assert_eq!("cigar", game.solution);
+        game.set_puzzle(196).unwrap();
+        assert_eq!("rebus", game.solution);
+        assert!(game.set_puzzle(100_000).is_err());
+    }
 }
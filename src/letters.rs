@@ -0,0 +1,104 @@
+use structopt::StructOpt;
+
+use wordsmith::stats::LetterStats;
+use wordsmith::words::{WordList, WordSource};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "ws-letters")]
+struct Opt {
+    // repeat to compare sources
+    #[structopt(short, long, default_value = "wordle")]
+    word_source: Vec<String>,
+    // letters and bigrams listed per line
+    #[structopt(long, default_value = "10")]
+    top: usize,
+}
+
+fn percent(share: f64) -> String {
+    format!("{:.1}%", 100.0 * share)
+}
+
+fn print_stats(name: &str, stats: &LetterStats, top: usize) {
+    println!(
+        "{}: {} words, {} letters",
+        name,
+        stats.words,
+        stats.total_letters()
+    );
+    let letters: Vec<String> = stats
+        .top_letters(top)
+        .into_iter()
+        .map(|(c, _)| format!("{} {}", c, percent(stats.letter_frequency(c))))
+        .collect();
+    println!("  letters:    {}", letters.join(", "));
+    for position in 0..stats.positions.len() {
+        let letters: Vec<String> = stats
+            .top_at(position, top)
+            .into_iter()
+            .map(|(c, _)| format!("{} {}", c, percent(stats.position_frequency(position, c))))
+            .collect();
+        println!("  position {}: {}", position + 1, letters.join(", "));
+    }
+    let bigrams: Vec<String> = stats
+        .top_bigrams(top)
+        .into_iter()
+        .map(|(b, _)| format!("{} {}", b, percent(stats.bigram_frequency(&b))))
+        .collect();
+    println!("  bigrams:    {}", bigrams.join(", "));
+    println!(
+        "  repeated letters in {} of words, double letters in {}",
+        percent(stats.repeated as f64 / stats.words.max(1) as f64),
+        percent(stats.doubles as f64 / stats.words.max(1) as f64),
+    );
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let mut solutions: Vec<(String, LetterStats)> = Vec::new();
+    for name in &opt.word_source {
+        let source: WordSource = name.parse().expect("invalid word source");
+        let wordlist = WordList::from_source(&source).expect("failed to load word list");
+        let stats = LetterStats::solutions(&wordlist);
+        print_stats(&format!("{} solutions", name), &stats, opt.top);
+        print_stats(
+            &format!("{} guesses", name),
+            &LetterStats::guesses(&wordlist),
+            opt.top,
+        );
+        println!();
+        solutions.push((name.to_string(), stats));
+    }
+    if solutions.len() < 2 {
+        return;
+    }
+    // share of solutions containing each of the first source's top letters
+    println!("Words containing each letter, solutions:");
+    print!("{:<8}", "letter");
+    for (name, _) in &solutions {
+        print!(" {:>20}", name);
+    }
+    println!();
+    for (c, _) in solutions[0].1.top_letters(opt.top) {
+        print!("{:<8}", c);
+        for (_, stats) in &solutions {
+            print!(" {:>20}", percent(stats.word_frequency(c)));
+        }
+        println!();
+    }
+    print!("{:<8}", "repeats");
+    for (_, stats) in &solutions {
+        print!(
+            " {:>20}",
+            percent(stats.repeated as f64 / stats.words.max(1) as f64)
+        );
+    }
+    println!();
+    print!("{:<8}", "doubles");
+    for (_, stats) in &solutions {
+        print!(
+            " {:>20}",
+            percent(stats.doubles as f64 / stats.words.max(1) as f64)
+        );
+    }
+    println!();
+}
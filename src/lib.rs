@@ -1,47 +1,72 @@
+use std::convert::TryFrom;
 use std::fmt;
 use std::ops::Index;
+use std::str::FromStr;
 
+use crate::alphabet::LETTERS;
+
+pub mod alphabet;
+pub mod analysis;
 pub mod counter;
+pub mod daily;
+pub mod error;
+pub mod families;
+pub mod feedback;
 pub mod game;
+pub mod prior;
+pub mod profile;
 pub mod solver;
+pub mod stats;
 pub mod words;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Letter(u8);
 
-impl Letter {
-    pub fn from_byte(byte: u8) -> Self {
-        if !(b'a'..=b'z').contains(&byte) {
-            panic!("letter {} out of bounds", byte)
-        }
-        Self(byte - 97)
-    }
-    pub fn from_char(c: char) -> Self {
-        if !('a'..='z').contains(&c) {
-            panic!("letter {} out of bounds", c)
-        }
-        Self::from_byte(c as u8)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidLetter(pub char);
+
+impl fmt::Display for InvalidLetter {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "invalid letter {:?}", self.0)
     }
+}
+
+impl std::error::Error for InvalidLetter {}
+
+impl Letter {
     pub fn as_index(&self) -> usize {
         (self.0).into()
     }
-    pub fn as_byte(&self) -> u8 {
-        self.0 + 97
-    }
     pub fn as_char(&self) -> char {
-        self.as_byte() as char
+        LETTERS[self.as_index()]
     }
 }
 
-impl From<Letter> for usize {
-    fn from(letter: Letter) -> Self {
-        letter.as_index()
+impl TryFrom<u8> for Letter {
+    type Error = InvalidLetter;
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        match byte {
+            b'a'..=b'z' => Ok(Self(byte - b'a')),
+            b'A'..=b'Z' => Ok(Self(byte - b'A')),
+            _ => Err(InvalidLetter(byte as char)),
+        }
+    }
+}
+
+impl TryFrom<char> for Letter {
+    type Error = InvalidLetter;
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        if c.is_ascii() {
+            Self::try_from(c as u8)
+        } else {
+            Err(InvalidLetter(c))
+        }
     }
 }
 
-impl From<Letter> for u8 {
+impl From<Letter> for usize {
     fn from(letter: Letter) -> Self {
-        letter.as_byte()
+        letter.as_index()
     }
 }
 
@@ -74,11 +99,22 @@ impl Default for Word {
     }
 }
 
-impl From<&str> for Word {
-    fn from(s: &str) -> Self {
-        Self {
-            vec: s.chars().map(Letter::from_char).collect(),
-        }
+impl TryFrom<&str> for Word {
+    type Error = InvalidLetter;
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Ok(Self {
+            vec: s
+                .chars()
+                .map(Letter::try_from)
+                .collect::<Result<Vec<Letter>, InvalidLetter>>()?,
+        })
+    }
+}
+
+impl FromStr for Word {
+    type Err = InvalidLetter;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
     }
 }
 
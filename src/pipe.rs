@@ -0,0 +1,217 @@
+use std::io::{self, BufRead, Write};
+use std::process;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use structopt::StructOpt;
+
+use wordsmith::daily::Puzzle;
+use wordsmith::error::Error;
+use wordsmith::game::{Game, GuessResult, State};
+use wordsmith::solver::{Solver, SolverMode, TieBreak};
+use wordsmith::words::{WordList, WordSource};
+
+#[derive(Debug)]
+enum Role {
+    Guesser,
+    Host,
+}
+
+impl FromStr for Role {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        match s {
+            "guesser" => Ok(Self::Guesser),
+            "host" => Ok(Self::Host),
+            _ => Err("role must be guesser or host".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "ws-pipe")]
+struct Opt {
+    // guesser: print guesses and read feedback; host: read guesses and print feedback
+    role: Role,
+    #[structopt(short, long, default_value = "wordle")]
+    word_source: WordSource,
+    #[structopt(short, long, default_value = "minev")]
+    mode: SolverMode,
+    // guesser tie break: candidate, common, distinct, lexicographic, random[:SEED] or score:MODE
+    #[structopt(long, default_value = "candidate")]
+    tie_break: TieBreak,
+    // hidden solution for the host, random if not given
+    #[structopt(short, long)]
+    solution: Option<String>,
+    // daily puzzle for the host: today, a date (YYYY-MM-DD) or a puzzle number
+    #[structopt(short, long)]
+    daily: Option<Puzzle>,
+    #[structopt(long)]
+    seed: Option<u64>,
+    #[structopt(short, long, default_value = "6")]
+    tries: usize,
+}
+
+fn read_line<R: BufRead>(input: &mut R) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+fn guesser<R: BufRead, W: Write>(
+    game: &mut Game,
+    mode: &SolverMode,
+    tie_break: &TieBreak,
+    input: &mut R,
+    output: &mut W,
+) -> Result<State, Error> {
+    let mut solver = Solver::new(game);
+    solver.tie_break = tie_break.clone();
+    while let State::Unsolved = solver.game.state() {
+        let guess = solver.suggest(mode)?;
+        writeln!(output, "{}", guess)?;
+        output.flush()?;
+        let feedback = match read_line(input)? {
+            Some(feedback) => feedback,
+            None => break,
+        };
+        solver.observe(GuessResult::from_feedback(guess, &feedback)?)?;
+    }
+    Ok(solver.game.state())
+}
+
+fn host<R: BufRead, W: Write>(
+    game: &mut Game,
+    input: &mut R,
+    output: &mut W,
+) -> Result<State, Error> {
+    while let State::Unsolved = game.state() {
+        let guess = match read_line(input)? {
+            Some(guess) => guess,
+            None => break,
+        };
+        // invalid guesses do not use up a try
+        match game.guess(guess) {
+            Ok(result) => writeln!(output, "{}", result.feedback())?,
+            Err(e) => writeln!(output, "error {}", e)?,
+        }
+        output.flush()?;
+    }
+    Ok(game.state())
+}
+
+fn start(opt: &Opt) -> Result<Game, Error> {
+    let wordlist = Rc::new(WordList::from_source(&opt.word_source)?);
+    let mut game = match opt.seed {
+        Some(seed) => Game::from_wordlist_seeded(wordlist, seed)?,
+        None => Game::from_wordlist(wordlist)?,
+    };
+    game.tries = opt.tries;
+    if let Some(solution) = &opt.solution {
+        game.set_solution(solution.to_string())?;
+    } else if let Some(puzzle) = &opt.daily {
+        game.set_puzzle(puzzle.number()?)?;
+    }
+    Ok(game)
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let mut game = match start(&opt) {
+        Ok(game) => game,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let (mut input, mut output) = (stdin.lock(), stdout.lock());
+    let state = match opt.role {
+        Role::Guesser => guesser(
+            &mut game,
+            &opt.mode,
+            &opt.tie_break,
+            &mut input,
+            &mut output,
+        ),
+        Role::Host => host(&mut game, &mut input, &mut output),
+    };
+    match state {
+        Ok(State::Solved) => eprintln!("Solved in {} guesses.", game.guesses.len()),
+        // the guesser never learns the host's solution
+        Ok(State::Failed) => match opt.role {
+            Role::Host => eprintln!("Failed to solve {}.", game.solution),
+            Role::Guesser => eprintln!("Failed to solve in {} guesses.", game.guesses.len()),
+        },
+        Ok(State::Unsolved) => {
+            eprintln!("Game interrupted after {} guesses.", game.guesses.len());
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+
+    fn game() -> Game {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("relax".to_string()).unwrap();
+        game
+    }
+
+    #[test]
+    fn test_host() {
+        let mut game = game();
+        let mut input = Cursor::new("tares\nxxxxx\nRelax\nlover\n");
+        let mut output = vec![];
+        let state = host(&mut game, &mut input, &mut output).unwrap();
+        assert!(matches!(state, State::Solved));
+        assert_eq!(2, game.guesses.len());
+        assert_eq!(
+            "byyyb\nerror invalid guess \"xxxxx\"\nggggg\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_guesser() {
+        let mut game = game();
+        let mut input = Cursor::new("ggggg\n");
+        let mut output = vec![];
+        let state = guesser(
+            &mut game,
+            &SolverMode::MinEV,
+            &TieBreak::Candidate,
+            &mut input,
+            &mut output,
+        )
+        .unwrap();
+        assert!(matches!(state, State::Solved));
+        assert_eq!("roate\n", String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn test_guesser_bad_feedback() {
+        let mut game = game();
+        let mut input = Cursor::new("gg\n");
+        let mut output = vec![];
+        let result = guesser(
+            &mut game,
+            &SolverMode::MinEV,
+            &TieBreak::Candidate,
+            &mut input,
+            &mut output,
+        );
+        assert!(matches!(result, Err(Error::InvalidFeedback(_))));
+    }
+}
@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use structopt::StructOpt;
+
+use wordsmith::analysis::GameReport;
+use wordsmith::daily::{Date, Puzzle};
+use wordsmith::error::Error;
+use wordsmith::game::{Game, GuessResult, LetterMatch, LetterState, State};
+use wordsmith::solver::{Solver, SolverMode};
+use wordsmith::words::{Lengths, WordList, WordSource};
+use wordsmith::Letter;
+
+const KEYBOARD: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+const HINT: &str = "?";
+// smallest family of possible solutions worth a warning
+const TRAP_SIZE: usize = 3;
+
+const GREEN: &str = "\x1b[1;30;42m";
+const YELLOW: &str = "\x1b[1;30;43m";
+const GRAY: &str = "\x1b[1;37;100m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "ws-play")]
+struct Opt {
+    #[structopt(short, long, default_value = "wordle")]
+    word_source: WordSource,
+    // solver mode used for hints
+    #[structopt(short, long, default_value = "minev")]
+    mode: SolverMode,
+    // first game is the daily puzzle: today, a date (YYYY-MM-DD) or a puzzle number
+    #[structopt(short, long, conflicts_with = "lengths")]
+    daily: Option<Puzzle>,
+    #[structopt(long)]
+    seed: Option<u64>,
+    // rule out the answers of the puzzles before this one: today, a date or a puzzle number
+    #[structopt(long)]
+    exclude_before: Option<Puzzle>,
+    // rule out the answers listed in a file, one per line
+    #[structopt(long)]
+    used: Option<PathBuf>,
+    // each game picks a length, e.g. 4-11, and a word of that length from the word source
+    #[structopt(long)]
+    lengths: Option<Lengths>,
+}
+
+fn tile(c: char, color: Option<&str>) -> String {
+    let c = c.to_uppercase();
+    match color {
+        Some(color) => format!("{} {} {}", color, c, RESET),
+        None => format!(" {} ", c),
+    }
+}
+
+fn render_guess(result: &GuessResult) -> String {
+    result
+        .guess
+        .vec
+        .iter()
+        .zip(&result.result)
+        .map(|(letter, m)| {
+            let color = match m {
+                LetterMatch::Exact => GREEN,
+                LetterMatch::Partial => YELLOW,
+                LetterMatch::Wrong => GRAY,
+            };
+            tile(letter.as_char(), Some(color))
+        })
+        .collect()
+}
+
+fn render_key(letter: Letter, states: &HashMap<Letter, LetterState>) -> String {
+    let color = match states.get(&letter) {
+        Some(LetterState::Exact) => Some(GREEN),
+        Some(LetterState::Partial) => Some(YELLOW),
+        Some(LetterState::Eliminated) => Some(GRAY),
+        Some(LetterState::Unknown) | None => None,
+    };
+    tile(letter.as_char(), color)
+}
+
+fn render_keyboard(game: &Game) -> Vec<String> {
+    let alphabet = game.wordlist.alphabet;
+    let states = game.letter_states();
+    let mut rows: Vec<Vec<Letter>> = KEYBOARD
+        .iter()
+        .map(|row| {
+            row.chars()
+                .filter_map(|c| alphabet.letter(c).ok())
+                .collect()
+        })
+        .collect();
+    // letters outside of the qwerty layout, e.g. ñ or ß
+    let extra: Vec<Letter> = alphabet
+        .letters()
+        .into_iter()
+        .filter(|l| !KEYBOARD.iter().any(|row| row.contains(l.as_char())))
+        .collect();
+    if !extra.is_empty() {
+        rows.push(extra);
+    }
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let keys: String = row.iter().map(|l| render_key(*l, &states)).collect();
+            format!("{}{}", " ".repeat(i * 2), keys)
+        })
+        .collect()
+}
+
+fn render(game: &Game) {
+    println!();
+    for i in 0..game.tries {
+        match game.guesses.get(i) {
+            Some(result) => println!("  {}", render_guess(result)),
+            None => println!("  {}", " _ ".repeat(game.letter_count)),
+        }
+    }
+    println!();
+    for row in render_keyboard(game) {
+        println!("  {}", row);
+    }
+    println!();
+}
+
+fn hint(game: &mut Game, mode: &SolverMode) -> Result<String, Error> {
+    let solver = Solver::new(game);
+    let guess = solver.suggest(mode)?;
+    let mut message = format!(
+        "Hint: {} ({} possible solutions left)",
+        guess,
+        solver.possible_solutions.len()
+    );
+    if let Some(family) = solver.live_family(TRAP_SIZE) {
+        let splitters: Vec<String> = family
+            .splitters(
+                &solver.game.wordlist.alphabet,
+                &solver.guess_words(),
+                family.guesses_lower_bound() + 1,
+            )
+            .iter()
+            .map(String::from)
+            .collect();
+        message.push_str(&format!(
+            "\nTrap: {} ({}), split with {}",
+            family.key,
+            family.members.join(", "),
+            splitters.join(", ")
+        ));
+    }
+    Ok(message)
+}
+
+fn prompt(message: &str) -> io::Result<Option<String>> {
+    print!("{}", message);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+fn play(game: &mut Game, mode: &SolverMode) -> io::Result<bool> {
+    render(game);
+    while let State::Unsolved = game.state() {
+        let input = match prompt(&format!("Guess ({} for a hint): ", HINT))? {
+            Some(input) => input,
+            None => return Ok(false),
+        };
+        if input == HINT {
+            match hint(game, mode) {
+                Ok(message) => println!("{}", message),
+                Err(e) => println!("No hint: {}", e),
+            }
+            continue;
+        }
+        match game.guess(input) {
+            Ok(_) => render(game),
+            Err(Error::InvalidGuess(guess)) => println!("Not in word list: {}", guess),
+            Err(e) => println!("{}", e),
+        }
+    }
+    match game.state() {
+        State::Solved => println!("Solved in {}/{}!", game.guesses.len(), game.tries),
+        _ => println!("The word was {}.", game.solution.to_uppercase()),
+    }
+    match GameReport::new(game) {
+        Ok(report) => print!("\n{}\n", report),
+        Err(e) => println!("No analysis: {}", e),
+    }
+    let again = prompt("Play again? [y/N] ")?;
+    Ok(matches!(again.as_deref(), Some("y") | Some("Y")))
+}
+
+fn start(opt: &Opt) -> Result<(Game, Vec<Rc<WordList>>), Error> {
+    let mut wordlists = match opt.lengths {
+        Some(lengths) => WordList::from_source_lengths(&opt.word_source, lengths)?,
+        None => vec![WordList::from_source(&opt.word_source)?],
+    };
+    let mut excluded = 0;
+    for wordlist in wordlists.iter_mut() {
+        if let Some(puzzle) = &opt.exclude_before {
+            excluded += wordlist.exclude_before(puzzle.number()?)?;
+        }
+        if let Some(path) = &opt.used {
+            excluded += wordlist.exclude_solutions_from_file(path)?;
+        }
+    }
+    if excluded > 0 {
+        println!("Excluded {} used answers.", excluded);
+    }
+    let wordlists: Vec<Rc<WordList>> = wordlists.into_iter().map(Rc::new).collect();
+    let mut game = match opt.seed {
+        Some(seed) => Game::from_wordlist_seeded(wordlists[0].clone(), seed)?,
+        None => Game::from_wordlist(wordlists[0].clone())?,
+    };
+    if let Some(puzzle) = &opt.daily {
+        let number = puzzle.number()?;
+        game.set_puzzle(number)?;
+        println!("Puzzle #{} ({})", number, Date::from_puzzle_number(number));
+    } else if wordlists.len() > 1 {
+        next_game(&mut game, &wordlists)?;
+    }
+    Ok((game, wordlists))
+}
+
+// Picks the next solution, and its length when there are several.
+fn next_game(game: &mut Game, wordlists: &[Rc<WordList>]) -> Result<(), Error> {
+    if wordlists.len() == 1 {
+        return game.pick_solution();
+    }
+    game.pick_wordlist(wordlists)?;
+    println!("{} letters", game.letter_count);
+    Ok(())
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let (mut game, wordlists) = match start(&opt) {
+        Ok(started) => started,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    loop {
+        match play(&mut game, &opt.mode) {
+            Ok(true) => {
+                game.restart();
+                next_game(&mut game, &wordlists).unwrap();
+            }
+            Ok(false) => break,
+            Err(e) => {
+                eprintln!("{}", e);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn game() -> Game {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("relax".to_string()).unwrap();
+        let guess = game.wordlist.alphabet.word("tares").unwrap();
+        game.record(GuessResult::from_feedback(guess, "byyyb").unwrap())
+            .unwrap();
+        game
+    }
+
+    #[test]
+    fn test_tile() {
+        assert_eq!(" Q ", tile('q', None));
+        assert_eq!(format!("{} A {}", GREEN, RESET), tile('a', Some(GREEN)));
+    }
+
+    #[test]
+    fn test_render_guess() {
+        let game = game();
+        let expected = format!(
+            "{}{}{}{}{}",
+            tile('t', Some(GRAY)),
+            tile('a', Some(YELLOW)),
+            tile('r', Some(YELLOW)),
+            tile('e', Some(YELLOW)),
+            tile('s', Some(GRAY))
+        );
+        assert_eq!(expected, render_guess(&game.guesses[0]));
+    }
+
+    #[test]
+    fn test_render_keyboard() {
+        let rows = render_keyboard(&game());
+        assert_eq!(3, rows.len());
+        // rows are indented like a keyboard, guessed letters are shaded
+        assert!(rows[0].starts_with(&tile('q', None)));
+        assert!(rows[0].contains(&tile('t', Some(GRAY))));
+        assert!(rows[0].contains(&tile('r', Some(YELLOW))));
+        assert!(rows[1].starts_with(&format!("  {}", tile('a', Some(YELLOW)))));
+        assert!(rows[1].contains(&tile('s', Some(GRAY))));
+        assert!(rows[2].starts_with(&format!("    {}", tile('z', None))));
+    }
+
+    #[test]
+    fn test_hint() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        let message = hint(&mut game, &SolverMode::MinEV).unwrap();
+        assert!(message.starts_with("Hint: roate (2315 possible solutions left)"));
+    }
+}
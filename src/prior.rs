@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::words::WordList;
+
+// Probability model over the solutions of a word list.
+#[derive(Debug, Clone, Default)]
+pub enum Prior {
+    #[default]
+    Uniform,
+    // sigmoid of the frequency rank, 1/2 at rank `center`; defaults to the number of
+    // answers and a tenth of it
+    Frequency {
+        center: Option<usize>,
+        width: Option<f64>,
+    },
+}
+
+impl Prior {
+    // Unnormalized weight of each word, in the order given.
+    pub fn weights(&self, wordlist: &WordList, words: &[String]) -> Vec<f64> {
+        let (center, width) = match self {
+            Self::Uniform => return vec![1.0; words.len()],
+            Self::Frequency { center, width } => {
+                let center = center.unwrap_or_else(|| wordlist.answers.len()) as f64;
+                (center, width.unwrap_or(center / 10.0).max(1.0))
+            }
+        };
+        let ranks: HashMap<&str, usize> = wordlist
+            .words_by_frequency
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (w.as_str(), i))
+            .collect();
+        words
+            .iter()
+            .map(|w| {
+                let rank = ranks.get(w.as_str()).copied().unwrap_or(ranks.len()) as f64;
+                // keep far tails positive so every candidate stays possible
+                (1.0 / (1.0 + ((rank - center) / width).exp())).max(f64::MIN_POSITIVE)
+            })
+            .collect()
+    }
+
+    // Draws `n` distinct solutions from the prior, likelier ones being drawn earlier. Every
+    // solution is drawn when `n` is at least their number.
+    pub fn sample<R: Rng>(&self, wordlist: &WordList, n: usize, rng: &mut R) -> Vec<String> {
+        // allowed_solutions is unordered, sort for reproducible samples
+        let mut solutions: Vec<String> = wordlist.allowed_solutions.iter().cloned().collect();
+        solutions.sort();
+        if n >= solutions.len() {
+            return solutions;
+        }
+        let weights = self.weights(wordlist, &solutions);
+        let indices: Vec<usize> = (0..solutions.len()).collect();
+        indices
+            .choose_multiple_weighted(rng, n, |&i| weights[i])
+            .expect("prior weights are positive")
+            .map(|&i| solutions[i].clone())
+            .collect()
+    }
+}
+
+impl FromStr for Prior {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let parts: Vec<&str> = s.split(',').collect();
+        match (parts[0], parts.len()) {
+            ("uniform", 1) => Ok(Self::Uniform),
+            ("frequency", 1..=3) => {
+                let center = match parts.get(1) {
+                    Some(c) => Some(c.parse().map_err(|e| format!("{}", e))?),
+                    None => None,
+                };
+                let width = match parts.get(2) {
+                    Some(w) => Some(w.parse().map_err(|e| format!("{}", e))?),
+                    None => None,
+                };
+                Ok(Self::Frequency { center, width })
+            }
+            _ => Err("prior must be uniform or frequency[,CENTER[,WIDTH]]".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::words::WordSource;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_frequency_prior() {
+        let source = WordSource::Scrabble {
+            letter_count: 5,
+            top_n: 1000,
+        };
+        let wordlist = WordList::from_source(&source).unwrap();
+        let words = vec![
+            wordlist.words_by_frequency[0].clone(),
+            wordlist.words_by_frequency[1000].clone(),
+            wordlist.words_by_frequency[5000].clone(),
+        ];
+        let weights = "frequency"
+            .parse::<Prior>()
+            .unwrap()
+            .weights(&wordlist, &words);
+        assert!(weights[0] > 0.99);
+        assert!((weights[1] - 0.5).abs() < 1e-9);
+        assert!(weights[2] < 1e-9 && weights[2] > 0.0);
+        assert_eq!(vec![1.0; 3], Prior::Uniform.weights(&wordlist, &words));
+        assert!("frequency,10,x".parse::<Prior>().is_err());
+        assert!("uniform,1".parse::<Prior>().is_err());
+    }
+
+    #[test]
+    fn test_sample() {
+        let wordlist = WordList::from_source(&WordSource::Wordle).unwrap();
+        let prior = Prior::Uniform;
+        let a = prior.sample(&wordlist, 20, &mut StdRng::seed_from_u64(7));
+        let b = prior.sample(&wordlist, 20, &mut StdRng::seed_from_u64(7));
+        assert_eq!(a, b);
+        assert!(a.iter().all(|w| wordlist.is_valid_solution(w)));
+        // no solution is drawn twice, and large samples cover them all
+        let mut c = "frequency".parse::<Prior>().unwrap().sample(
+            &wordlist,
+            2000,
+            &mut StdRng::seed_from_u64(7),
+        );
+        c.sort();
+        c.dedup();
+        assert_eq!(2000, c.len());
+        assert_eq!(
+            2315,
+            prior
+                .sample(&wordlist, 5000, &mut StdRng::seed_from_u64(7))
+                .len()
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default)]
+pub struct TurnStats {
+    // number of times the turn was played
+    pub count: usize,
+    pub find_guess: Duration,
+    pub filter: Duration,
+    // feedback computations, i.e. guess and solution pairs checked
+    pub feedbacks: u64,
+}
+
+impl TurnStats {
+    fn merge(&mut self, other: &TurnStats) {
+        self.count += other.count;
+        self.find_guess += other.find_guess;
+        self.filter += other.filter;
+        self.feedbacks += other.feedbacks;
+    }
+}
+
+// Time and work spent by a solver, per turn.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    // turns[0] is the first turn
+    pub turns: Vec<TurnStats>,
+}
+
+impl Profile {
+    pub fn turn(&mut self, turn: usize) -> &mut TurnStats {
+        if self.turns.len() < turn {
+            self.turns.resize(turn, TurnStats::default());
+        }
+        &mut self.turns[turn - 1]
+    }
+    pub fn merge(&mut self, other: &Profile) {
+        for (i, stats) in other.turns.iter().enumerate() {
+            self.turn(i + 1).merge(stats);
+        }
+    }
+    pub fn total(&self) -> TurnStats {
+        let mut total = TurnStats::default();
+        for stats in &self.turns {
+            total.merge(stats);
+        }
+        total
+    }
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            fmt,
+            "{:>5} {:>7} {:>12} {:>12} {:>10} {:>14} {:>12}",
+            "turn", "count", "find ms", "filter ms", "time %", "feedbacks", "per turn"
+        )?;
+        let total = self.total();
+        let total_ms = ms(total.find_guess + total.filter).max(f64::MIN_POSITIVE);
+        let rows = self
+            .turns
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (format!("{}", i + 1), s));
+        for (name, stats) in rows.chain(std::iter::once(("total".to_string(), &total))) {
+            let count = stats.count.max(1) as f64;
+            writeln!(
+                fmt,
+                "{:>5} {:>7} {:>12.3} {:>12.3} {:>9.1}% {:>14} {:>12.0}",
+                name,
+                stats.count,
+                ms(stats.find_guess) / count,
+                ms(stats.filter) / count,
+                100.0 * ms(stats.find_guess + stats.filter) / total_ms,
+                stats.feedbacks,
+                stats.feedbacks as f64 / count,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+use std::rc::Rc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use structopt::StructOpt;
+use tiny_http::{Header, Method, Response, Server};
+
+use wordsmith::error::Error;
+use wordsmith::game::{Game, GuessResult};
+use wordsmith::solver::{Solver, SolverMode};
+use wordsmith::words::{WordList, WordSource};
+use wordsmith::Word;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "ws-server")]
+struct Opt {
+    #[structopt(short, long, default_value = "127.0.0.1:8000")]
+    address: String,
+    // the first source is used when a request does not name one
+    #[structopt(short, long, default_value = "wordle")]
+    word_source: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Turn {
+    guess: String,
+    feedback: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Query {
+    source: Option<String>,
+    mode: Option<String>,
+    guesses: Vec<Turn>,
+    guess: Option<String>,
+    solution: Option<String>,
+}
+
+struct Service {
+    wordlists: Vec<(String, Rc<WordList>)>,
+}
+
+type Reply = Result<Value, (u16, String)>;
+
+fn bad_request<E: ToString>(e: E) -> (u16, String) {
+    (400, e.to_string())
+}
+
+impl Service {
+    fn new(sources: &[String]) -> Result<Self, String> {
+        let mut wordlists = vec![];
+        for name in sources {
+            let source: WordSource = name.parse()?;
+            let wordlist = WordList::from_source(&source).map_err(|e| e.to_string())?;
+            wordlists.push((name.to_string(), Rc::new(wordlist)));
+        }
+        Ok(Self { wordlists })
+    }
+
+    fn wordlist(&self, query: &Query) -> Result<Rc<WordList>, (u16, String)> {
+        let found = match &query.source {
+            None => self.wordlists.first(),
+            Some(name) => self.wordlists.iter().find(|(n, _)| n == name),
+        };
+        match found {
+            Some((_, wordlist)) => Ok(wordlist.clone()),
+            None => Err(bad_request("unknown word source")),
+        }
+    }
+
+    fn word(
+        &self,
+        wordlist: &WordList,
+        s: &Option<String>,
+        field: &str,
+    ) -> Result<Word, (u16, String)> {
+        let s = match s {
+            Some(s) => s,
+            None => return Err(bad_request(format!("missing {}", field))),
+        };
+        let word = wordlist.alphabet.word(s).map_err(bad_request)?;
+        if word.len() != wordlist.source.letter_count() {
+            return Err(bad_request(format!(
+                "{} must have {} letters",
+                field,
+                wordlist.source.letter_count()
+            )));
+        }
+        Ok(word)
+    }
+
+    fn mode(&self, query: &Query) -> Result<SolverMode, (u16, String)> {
+        match &query.mode {
+            Some(mode) => mode.parse().map_err(bad_request),
+            None => Ok(SolverMode::MinEV),
+        }
+    }
+
+    fn game(&self, query: &Query) -> Result<Game, (u16, String)> {
+        let wordlist = self.wordlist(query)?;
+        let mut game = Game::from_wordlist(wordlist.clone()).map_err(bad_request)?;
+        for turn in &query.guesses {
+            let word = self.word(&wordlist, &Some(turn.guess.clone()), "guess")?;
+            let result = GuessResult::from_feedback(word, &turn.feedback).map_err(bad_request)?;
+            game.record(result).map_err(bad_request)?;
+        }
+        Ok(game)
+    }
+
+    fn suggest(&self, query: &Query) -> Reply {
+        let mode = self.mode(query)?;
+        let mut game = self.game(query)?;
+        let solver = Solver::new(&mut game);
+        let guess = solver.suggest(&mode).map_err(bad_request)?;
+        Ok(json!({
+            "guess": guess.to_string(),
+            "candidates": solver.possible_solutions.len(),
+        }))
+    }
+
+    fn candidates(&self, query: &Query) -> Reply {
+        let mut game = self.game(query)?;
+        let mut candidates = Solver::new(&mut game).possible_solutions;
+        candidates.sort();
+        Ok(json!({
+            "count": candidates.len(),
+            "candidates": candidates,
+        }))
+    }
+
+    fn score(&self, query: &Query) -> Reply {
+        let mode = self.mode(query)?;
+        let mut game = self.game(query)?;
+        let guess = self.word(&game.wordlist.clone(), &query.guess, "guess")?;
+        let solver = Solver::new(&mut game);
+        if solver.possible_solutions.is_empty() {
+            return Err(bad_request(Error::NoSolutions));
+        }
+        Ok(json!({
+            "guess": guess.to_string(),
+            "mode": format!("{:?}", mode),
+            "score": solver.compute_score(&guess, &mode),
+        }))
+    }
+
+    fn check(&self, query: &Query) -> Reply {
+        let wordlist = self.wordlist(query)?;
+        let guess = self.word(&wordlist, &query.guess, "guess")?;
+        let solution = self.word(&wordlist, &query.solution, "solution")?;
+        let result = GuessResult::check(&guess, &solution, guess.len());
+        Ok(json!({
+            "feedback": result.feedback(),
+            "solved": result.is_solved(),
+        }))
+    }
+
+    fn handle(&self, method: &Method, path: &str, body: &str) -> (u16, Value) {
+        let reply = match (method, path) {
+            (Method::Get, "/sources") => Ok(json!({
+                "sources": self.wordlists.iter().map(|(n, _)| n).collect::<Vec<&String>>(),
+            })),
+            (Method::Post, _) => match serde_json::from_str::<Query>(body) {
+                Ok(query) => match path {
+                    "/suggest" => self.suggest(&query),
+                    "/candidates" => self.candidates(&query),
+                    "/score" => self.score(&query),
+                    "/check" => self.check(&query),
+                    _ => Err((404, "not found".to_string())),
+                },
+                Err(e) => Err(bad_request(e)),
+            },
+            _ => Err((404, "not found".to_string())),
+        };
+        match reply {
+            Ok(value) => (200, value),
+            Err((status, message)) => (status, json!({ "error": message })),
+        }
+    }
+}
+
+// Path of a request URL, without the query string.
+fn route(url: &str) -> &str {
+    url.split('?').next().unwrap_or(url)
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let service = Service::new(&opt.word_source).expect("failed to load word lists");
+    let server = Server::http(&opt.address).expect("failed to start server");
+    println!("Listening on {}", opt.address);
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let (status, value) = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => service.handle(request.method(), route(request.url()), &body),
+            Err(e) => (400, json!({ "error": e.to_string() })),
+        };
+        println!("{} {} {}", request.method(), request.url(), status);
+        let response = Response::from_string(value.to_string())
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        if let Err(e) = request.respond(response) {
+            println!("Failed to respond: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn post(path: &str, body: Value) -> (u16, Value) {
+        let service = Service::new(&["wordle".to_string()]).unwrap();
+        service.handle(&Method::Post, path, &body.to_string())
+    }
+
+    #[test]
+    fn test_check() {
+        let (status, value) = post("/check", json!({"guess": "TARES", "solution": "relax"}));
+        assert_eq!(200, status);
+        assert_eq!(json!({"feedback": "byyyb", "solved": false}), value);
+    }
+
+    #[test]
+    fn test_candidates_and_suggest() {
+        let guesses = json!([
+            {"guess": "tares", "feedback": "byyyb"},
+            {"guess": "lover", "feedback": "ybbyy"},
+        ]);
+        let (status, value) = post("/candidates", json!({ "guesses": guesses }));
+        assert_eq!(200, status);
+        assert!(value["candidates"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("relax")));
+
+        let count = value["count"].clone();
+        let (status, value) = post("/suggest", json!({ "guesses": guesses }));
+        assert_eq!(200, status);
+        assert_eq!(count, value["candidates"]);
+        assert_eq!(5, value["guess"].as_str().unwrap().len());
+
+        let (status, value) = post("/suggest", json!({}));
+        assert_eq!(json!({"guess": "roate", "candidates": 2315}), value);
+        assert_eq!(200, status);
+    }
+
+    #[test]
+    fn test_score() {
+        let (status, value) = post("/score", json!({"guess": "tares", "mode": "minimax"}));
+        assert_eq!(200, status);
+        assert!(value["score"].as_f64().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let (status, value) = post("/check", json!({"guess": "tares"}));
+        assert_eq!(400, status);
+        assert_eq!(json!({"error": "missing solution"}), value);
+        let (status, _) = post(
+            "/suggest",
+            json!({"guesses": [{"guess": "tares", "feedback": "gg"}]}),
+        );
+        assert_eq!(400, status);
+        let (status, _) = post("/nothing", json!({}));
+        assert_eq!(404, status);
+    }
+
+    #[test]
+    fn test_query_string() {
+        assert_eq!("/sources", route("/sources"));
+        assert_eq!("/suggest", route("/suggest?"));
+        let (status, value) = post(route("/suggest?foo=1"), json!({}));
+        assert_eq!(200, status);
+        assert_eq!(json!("roate"), value["guess"]);
+    }
+}
@@ -16,35 +16,41 @@ struct Opt {
 
 fn main() {
     let opt = Opt::from_args();
-    let mut game = Game::from_source(&opt.word_source);
+    let mut game = Game::from_source(&opt.word_source).expect("failed to load word list");
     let wordlist = game.wordlist.clone();
     let solver: Solver = Solver::new(&mut game);
 
     let mut best_guess: String = "".to_string();
     let mut best_ev: f64 = f64::MAX;
     let wordlist_len = wordlist.words.len();
-    let mut results: Vec<(String, f64)> = vec![];
+    let mut results: Vec<(String, f64, f64)> = vec![];
     for (i, guess) in wordlist.words.iter().enumerate() {
-        let guess_word: Word = (&guess[..]).into();
+        let guess_word: Word = wordlist.alphabet.word(guess).unwrap();
         let ev = solver.compute_score(&guess_word, &opt.mode);
-        results.push((guess.to_string(), ev));
+        let bits = solver.expected_bits(&guess_word);
+        results.push((guess.to_string(), ev, bits));
         if ev < best_ev {
             best_guess = guess.to_string();
             best_ev = ev;
         }
         println!(
-            "{}/{} Evaluated {}: {:.2}. Best: {}: {:.2}",
+            "{}/{} Evaluated {}: {:.2} ({:.3} bits). Best: {}: {:.2}",
             i + 1,
             wordlist_len,
             guess,
             ev,
+            bits,
             best_guess,
             best_ev,
         );
     }
-    results.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-    println!("Top 10 for {:?} ({:?})", opt.word_source, opt.mode);
-    for (guess, score) in results.iter().take(10) {
-        println!("{}: {:.2}", guess, score);
+    // most expected bits first, then best score
+    results.sort_by(|a, b| b.2.total_cmp(&a.2).then(a.1.total_cmp(&b.1)));
+    println!(
+        "Top 10 by expected bits for {:?} ({:?} scores)",
+        opt.word_source, opt.mode
+    );
+    for (guess, score, bits) in results.iter().take(10) {
+        println!("{}: {:.2} ({:.3} bits)", guess, score, bits);
     }
 }
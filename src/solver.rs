@@ -1,15 +1,50 @@
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 use std::str::FromStr;
+use std::time::Instant;
 
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::error::Error;
+use crate::families::{self, Family};
+use crate::feedback::{feedback, feedback_batch, PackedWord, Pattern};
 use crate::game::{Game, GuessResult};
+use crate::prior::Prior;
+use crate::profile::{Profile, TurnStats};
+use crate::stats::LetterStats;
 use crate::words::WordSource;
 use crate::Word;
 
+// weight of the positional term of repeated letters in the letter frequency mode
+const REPEATED_LETTER_WEIGHT: f64 = 0.5;
+
 #[derive(Debug, Clone)]
 pub enum SolverMode {
     MinEV,
     MinLogEV,
     Minimax,
+    MaxEntropy,
+    // expected number of guesses, searching `depth` plies over the `top_k` best MinEV guesses
+    Lookahead {
+        top_k: usize,
+        depth: usize,
+    },
+    // probability of solving within `guesses` guesses, branching over the `top_k` best MinEV
+    // guesses for `depth` plies and playing the best MinEV guess after that
+    WithinN {
+        guesses: usize,
+        top_k: usize,
+        depth: usize,
+    },
+    // letter frequencies of the candidates, with exact MinEV scoring once there are at most
+    // `exact_below` candidates
+    LetterFrequency {
+        exact_below: usize,
+    },
 }
 
 impl FromStr for SolverMode {
@@ -19,12 +54,290 @@ impl FromStr for SolverMode {
             "minev" => Ok(Self::MinEV),
             "minlogev" => Ok(Self::MinLogEV),
             "minimax" => Ok(Self::Minimax),
+            "maxentropy" => Ok(Self::MaxEntropy),
+            mode if mode.starts_with("lookahead") => {
+                let parts: Vec<&str> = mode.split(',').collect();
+                if parts[0] != "lookahead" || parts.len() > 3 {
+                    return Err("lookahead mode must be lookahead[,TOP_K[,DEPTH]]".to_string());
+                }
+                let param = |i: usize, default: usize| match parts.get(i) {
+                    Some(p) => p.parse::<usize>().map_err(|e| e.to_string()),
+                    None => Ok(default),
+                };
+                let (top_k, depth) = (param(1, 10)?, param(2, 2)?);
+                if top_k == 0 || depth == 0 {
+                    return Err("lookahead TOP_K and DEPTH must be positive".to_string());
+                }
+                Ok(Self::Lookahead { top_k, depth })
+            }
+            mode if mode.starts_with("within") => {
+                let parts: Vec<&str> = mode.split(',').collect();
+                if parts[0] != "within" || parts.len() < 2 || parts.len() > 4 {
+                    return Err("within mode must be within,GUESSES[,TOP_K[,DEPTH]]".to_string());
+                }
+                let param = |i: usize, default: usize| match parts.get(i) {
+                    Some(p) => p.parse::<usize>().map_err(|e| e.to_string()),
+                    None => Ok(default),
+                };
+                let (guesses, top_k, depth) = (param(1, 6)?, param(2, 10)?, param(3, 1)?);
+                if guesses == 0 || top_k == 0 {
+                    return Err("within GUESSES and TOP_K must be positive".to_string());
+                }
+                Ok(Self::WithinN {
+                    guesses,
+                    top_k,
+                    depth,
+                })
+            }
+            mode if mode.starts_with("letterfreq") => {
+                let parts: Vec<&str> = mode.split(',').collect();
+                if parts[0] != "letterfreq" || parts.len() > 2 {
+                    return Err("letterfreq mode must be letterfreq[,EXACT_BELOW]".to_string());
+                }
+                let exact_below = match parts.get(1) {
+                    Some(p) => p.parse::<usize>().map_err(|e| e.to_string())?,
+                    None => 100,
+                };
+                Ok(Self::LetterFrequency { exact_below })
+            }
             _ => Err("invalid solver mode".to_string()),
         }
     }
 }
 
-pub fn first_guess(_mode: &SolverMode, source: &WordSource) -> Option<String> {
+// How to choose between guesses with the same score.
+#[derive(Debug, Clone, Default)]
+pub enum TieBreak {
+    // prefer possible solutions, then common words
+    #[default]
+    Candidate,
+    // prefer common words
+    Common,
+    // prefer fewer repeated letters
+    Distinct,
+    Lexicographic,
+    // seeded random choice, varying with the turn
+    Random(u64),
+    // lowest secondary score
+    Score(SolverMode),
+}
+
+impl FromStr for TieBreak {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let (name, param) = match s.split_once(':') {
+            Some((name, param)) => (name, Some(param)),
+            None => (s, None),
+        };
+        match (&name.to_lowercase()[..], param) {
+            ("candidate", None) => Ok(Self::Candidate),
+            ("common", None) => Ok(Self::Common),
+            ("distinct", None) => Ok(Self::Distinct),
+            ("lexicographic", None) => Ok(Self::Lexicographic),
+            ("random", None) => Ok(Self::Random(0)),
+            ("random", Some(seed)) => seed.parse().map(Self::Random).map_err(|e| format!("{}", e)),
+            ("score", Some(mode)) => Ok(Self::Score(mode.parse()?)),
+            _ => Err(
+                "tie break must be candidate, common, distinct, lexicographic, \
+                 random[:SEED] or score:MODE"
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+// Words the solver may guess.
+#[derive(Debug, Clone, Default)]
+pub enum GuessPool {
+    // every word of the word list
+    #[default]
+    All,
+    // only the possible solutions, i.e. easy mode
+    Candidates,
+    // the most common words, and the possible solutions
+    Top(usize),
+}
+
+impl GuessPool {
+    // Whether the pool changes with the possible solutions, in which case a pattern table of
+    // its guesses cannot carry over to the next turn.
+    pub fn depends_on_candidates(&self) -> bool {
+        match self {
+            Self::All => false,
+            Self::Candidates | Self::Top(_) => true,
+        }
+    }
+}
+
+impl FromStr for GuessPool {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        match s.split_once(',') {
+            None if s == "all" => Ok(Self::All),
+            None if s == "candidates" => Ok(Self::Candidates),
+            Some(("top", n)) => match n.parse() {
+                Ok(0) => Err("guess pool size must be positive".to_string()),
+                Ok(n) => Ok(Self::Top(n)),
+                Err(e) => Err(e.to_string()),
+            },
+            _ => Err("guess pool must be all, candidates or top,N".to_string()),
+        }
+    }
+}
+
+// Candidate indices by feedback.
+type Buckets = HashMap<Pattern, Vec<usize>>;
+
+// Feedback of every guess against every remaining candidate, shared by the whole
+// lookahead search.
+struct PatternTable {
+    guesses: Vec<Word>,
+    // patterns[guess][candidate]
+    patterns: Vec<Vec<Pattern>>,
+    weights: Vec<f64>,
+    solved: Pattern,
+}
+
+impl PatternTable {
+    fn buckets(row: &[Pattern], subset: &[usize]) -> Buckets {
+        let mut buckets = Buckets::new();
+        for &c in subset {
+            buckets.entry(row[c]).or_default().push(c);
+        }
+        buckets
+    }
+
+    // The table for a subset of the candidates, e.g. the bucket of the observed feedback.
+    fn restrict(&self, subset: &[usize]) -> Self {
+        Self {
+            guesses: self.guesses.clone(),
+            patterns: self
+                .patterns
+                .iter()
+                .map(|row| subset.iter().map(|&c| row[c]).collect())
+                .collect(),
+            weights: subset.iter().map(|&c| self.weights[c]).collect(),
+            solved: self.solved,
+        }
+    }
+
+    fn mass(&self, subset: &[usize]) -> f64 {
+        subset.iter().map(|&c| self.weights[c]).sum()
+    }
+
+    // MinEV score of a guess on the subset, and whether it is one of the candidates
+    fn minev(&self, guess: usize, subset: &[usize]) -> (f64, bool) {
+        let mut buckets: HashMap<Pattern, (usize, f64)> = HashMap::new();
+        for &c in subset {
+            let bucket = buckets.entry(self.patterns[guess][c]).or_insert((0, 0.0));
+            bucket.0 += 1;
+            bucket.1 += self.weights[c];
+        }
+        let candidate = buckets.contains_key(&self.solved);
+        let score = buckets.values().map(|(n, m)| *n as f64 * m).sum();
+        (score, candidate)
+    }
+
+    fn top_guesses(&self, subset: &[usize], top_k: usize) -> Vec<usize> {
+        let mut scored: Vec<(f64, bool, usize)> = (0..self.patterns.len())
+            .map(|g| {
+                let (score, candidate) = self.minev(g, subset);
+                (score, !candidate, g)
+            })
+            .collect();
+        scored.sort_unstable_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+        // modes built directly may ask for none, the search needs at least one
+        scored
+            .into_iter()
+            .take(top_k.max(1))
+            .map(|(_, _, g)| g)
+            .collect()
+    }
+
+    // Expected number of guesses to solve the subset, searching `depth` more plies.
+    fn expected_guesses(&self, subset: &[usize], top_k: usize, depth: usize) -> f64 {
+        let n = subset.len();
+        if n == 1 {
+            return 1.0;
+        }
+        if depth == 0 {
+            // lower bound: guess the likeliest candidate, then at least one more
+            let likeliest = subset.iter().map(|&c| self.weights[c]).fold(0.0, f64::max);
+            return 2.0 - likeliest / self.mass(subset);
+        }
+        self.top_guesses(subset, top_k)
+            .into_iter()
+            .map(|g| self.cost(&self.patterns[g], subset, top_k, depth.saturating_sub(1)))
+            .fold(f64::MAX, f64::min)
+    }
+
+    // Probability of solving the subset within `left` guesses.
+    fn p_solve(&self, subset: &[usize], left: usize, top_k: usize, depth: usize) -> f64 {
+        if left == 0 {
+            return 0.0;
+        }
+        if subset.len() == 1 || (subset.len() == 2 && left >= 2) {
+            return 1.0;
+        }
+        if left == 1 {
+            let likeliest = subset.iter().map(|&c| self.weights[c]).fold(0.0, f64::max);
+            return likeliest / self.mass(subset);
+        }
+        let k = if depth > 0 { top_k } else { 1 };
+        self.top_guesses(subset, k)
+            .into_iter()
+            .map(|g| self.p_solve_with(&self.patterns[g], subset, left, top_k, depth))
+            .fold(0.0, f64::max)
+    }
+
+    // Probability of solving within `left` guesses when playing the guess with this row.
+    fn p_solve_with(
+        &self,
+        row: &[Pattern],
+        subset: &[usize],
+        left: usize,
+        top_k: usize,
+        depth: usize,
+    ) -> f64 {
+        let total = self.mass(subset);
+        Self::buckets(row, subset)
+            .iter()
+            .map(|(pattern, bucket)| {
+                let p = self.mass(bucket) / total;
+                if *pattern == self.solved {
+                    p
+                } else {
+                    let depth = depth.saturating_sub(1);
+                    p * self.p_solve(bucket, left - 1, top_k, depth)
+                }
+            })
+            .sum()
+    }
+
+    // Expected number of guesses when playing the guess with this row of patterns.
+    fn cost(&self, row: &[Pattern], subset: &[usize], top_k: usize, depth: usize) -> f64 {
+        let total = self.mass(subset);
+        Self::buckets(row, subset)
+            .iter()
+            .map(|(pattern, bucket)| {
+                let p = self.mass(bucket) / total;
+                if *pattern == self.solved {
+                    p
+                } else {
+                    p * (1.0 + self.expected_guesses(bucket, top_k, depth))
+                }
+            })
+            .sum()
+    }
+}
+
+pub fn first_guess(mode: &SolverMode, source: &WordSource) -> Option<String> {
+    match (source, mode) {
+        (WordSource::Wordle, SolverMode::MaxEntropy) => return Some("soare".to_string()),
+        (WordSource::Wordle, _) => return Some("roate".to_string()),
+        (WordSource::File { .. }, _) => return None,
+        _ => {}
+    }
     match source.letter_count() {
         5 => Some("tares".to_string()),
         6 => Some("salter".to_string()),
@@ -37,114 +350,908 @@ pub fn first_guess(_mode: &SolverMode, source: &WordSource) -> Option<String> {
 pub struct Solver<'a> {
     pub game: &'a mut Game,
     pub possible_solutions: Vec<String>,
+    // prior weight of each possible solution
+    pub weights: Vec<f64>,
     pub first_guess: bool,
+    // first guess to play instead of the precomputed one, e.g. found once for many games
+    pub opener: Option<Word>,
+    pub tie_break: TieBreak,
+    pub guess_pool: GuessPool,
+    solution_words: Vec<Word>,
+    // None when the words are too long for the bit-parallel kernel
+    packed_solutions: Option<Vec<PackedWord>>,
+    // built on demand by the lookahead mode, cleared when the candidates change
+    pattern_table: RefCell<Option<Rc<PatternTable>>>,
+    // feedback buckets of the last suggested guess, as candidate indices, cleared when the
+    // candidates change
+    buckets: RefCell<Option<(Word, Buckets)>>,
+    // built on demand by the letter frequency mode, cleared when the candidates change
+    letter_stats: RefCell<Option<Rc<LetterStats>>>,
+    // feedback computations so far
+    feedbacks: Cell<u64>,
+    // None unless profiling is enabled
+    profile: RefCell<Option<Profile>>,
 }
 
 impl<'a> Solver<'a> {
     pub fn new(game: &'a mut Game) -> Self {
-        let mut possible_solutions: Vec<String> =
+        Self::with_prior(game, &Prior::Uniform)
+    }
+
+    pub fn with_prior(game: &'a mut Game, prior: &Prior) -> Self {
+        let possible_solutions: Vec<String> =
             game.wordlist.allowed_solutions.iter().cloned().collect();
-        let mut first_guess = true;
-        for guess in &game.guesses {
-            first_guess = false;
-            Self::filter_solutions(game, guess, &mut possible_solutions);
-        }
-        Self {
+        let weights = prior.weights(&game.wordlist, &possible_solutions);
+        let guesses = game.guesses.clone();
+        // precomputed first guesses are for the full answer list, all equally likely
+        let first_guess = guesses.is_empty()
+            && possible_solutions.len() == game.wordlist.answers.len()
+            && matches!(prior, Prior::Uniform);
+        let mut solver = Self {
             game,
             possible_solutions,
+            weights,
             first_guess,
+            opener: None,
+            tie_break: TieBreak::default(),
+            guess_pool: GuessPool::default(),
+            solution_words: vec![],
+            packed_solutions: None,
+            pattern_table: RefCell::new(None),
+            buckets: RefCell::new(None),
+            letter_stats: RefCell::new(None),
+            feedbacks: Cell::new(0),
+            profile: RefCell::new(None),
+        };
+        solver.index_solutions();
+        for guess in &guesses {
+            solver.filter_solutions(guess);
+        }
+        solver
+    }
+
+    fn word(&self, s: &str) -> Word {
+        self.game
+            .wordlist
+            .alphabet
+            .word(s)
+            .expect("word lists only contain valid words")
+    }
+
+    fn index_solutions(&mut self) {
+        self.solution_words = self
+            .possible_solutions
+            .iter()
+            .map(|s| self.word(s))
+            .collect();
+        self.packed_solutions = self.solution_words.iter().map(PackedWord::new).collect();
+        self.pattern_table.replace(None);
+        self.buckets.replace(None);
+        self.letter_stats.replace(None);
+    }
+
+    // Keeps the partition of the candidates by the feedback to a guess about to be played.
+    fn keep_buckets(&self, guess: &Word, row: &[Pattern]) {
+        let candidates: Vec<usize> = (0..row.len()).collect();
+        let buckets = PatternTable::buckets(row, &candidates);
+        self.buckets.replace(Some((guess.clone(), buckets)));
+    }
+
+    // Words of the guess pool, from most to least common.
+    pub fn guess_words(&self) -> Vec<Word> {
+        let words = &self.game.wordlist.words_by_frequency;
+        let candidates: HashSet<&String> = self.possible_solutions.iter().collect();
+        let pool: Vec<&String> = match self.guess_pool {
+            GuessPool::All => words.iter().collect(),
+            // uncommon solutions must stay playable
+            GuessPool::Top(n) => words
+                .iter()
+                .enumerate()
+                .filter(|(i, w)| *i < n || candidates.contains(w))
+                .map(|(_, w)| w)
+                .collect(),
+            GuessPool::Candidates => words.iter().filter(|w| candidates.contains(w)).collect(),
+        };
+        pool.into_iter().map(|s| self.word(s)).collect()
+    }
+
+    fn pattern_table(&self) -> Rc<PatternTable> {
+        if let Some(table) = &*self.pattern_table.borrow() {
+            return table.clone();
+        }
+        let guesses = self.guess_words();
+        let table = Rc::new(PatternTable {
+            patterns: guesses.iter().map(|g| self.patterns(g)).collect(),
+            guesses,
+            weights: self.weights.clone(),
+            solved: Pattern::solved(self.game.letter_count),
+        });
+        self.pattern_table.replace(Some(table.clone()));
+        table
+    }
+
+    fn compute_score_lookahead(&self, guess: &Word, top_k: usize, depth: usize) -> f64 {
+        let subset: Vec<usize> = (0..self.possible_solutions.len()).collect();
+        self.pattern_table().cost(
+            &self.patterns(guess),
+            &subset,
+            top_k,
+            depth.saturating_sub(1),
+        )
+    }
+
+    fn guesses_left(&self, guesses: usize) -> usize {
+        guesses.saturating_sub(self.game.guesses.len())
+    }
+
+    fn compute_score_within(
+        &self,
+        guess: &Word,
+        guesses: usize,
+        top_k: usize,
+        depth: usize,
+    ) -> f64 {
+        let subset: Vec<usize> = (0..self.possible_solutions.len()).collect();
+        let left = self.guesses_left(guesses);
+        if left == 0 {
+            return 0.0;
+        }
+        // scores are minimized
+        -self
+            .pattern_table()
+            .p_solve_with(&self.patterns(guess), &subset, left, top_k, depth)
+    }
+
+    fn find_guess_within(&self, guesses: usize, top_k: usize, depth: usize) -> Word {
+        let table = self.pattern_table();
+        let subset: Vec<usize> = (0..self.possible_solutions.len()).collect();
+        let left = self.guesses_left(guesses).max(1);
+        if left == 1 {
+            // only a candidate can still win, whether or not it is among the top guesses
+            return self.likeliest_candidate();
         }
+        let top = table.top_guesses(&subset, top_k);
+        let scored = top.into_iter().map(|g| {
+            let p = table.p_solve_with(&table.patterns[g], &subset, left, top_k, depth);
+            (g, -p, g)
+        });
+        let (guess, g) = match self.best_guess(&table.guesses, scored) {
+            Some(best) => best,
+            None => return self.likeliest_candidate(),
+        };
+        self.keep_buckets(&guess, &table.patterns[g]);
+        guess
+    }
+
+    // Candidate with the highest prior weight, ties broken like other guesses.
+    fn likeliest_candidate(&self) -> Word {
+        let weights: HashMap<&String, f64> = self
+            .possible_solutions
+            .iter()
+            .zip(self.weights.iter().copied())
+            .collect();
+        let likeliest = weights.values().copied().fold(0.0, f64::max);
+        // from most to least common
+        let tied: Vec<Word> = self
+            .game
+            .wordlist
+            .words_by_frequency
+            .iter()
+            .filter(|w| weights.get(w) == Some(&likeliest))
+            .map(|w| self.word(w))
+            .collect();
+        tied[self.break_tie(&tied)].clone()
+    }
+
+    fn find_guess_lookahead(&self, top_k: usize, depth: usize) -> Word {
+        let table = self.pattern_table();
+        let subset: Vec<usize> = (0..self.possible_solutions.len()).collect();
+        let top = table.top_guesses(&subset, top_k);
+        let scored = top.into_iter().map(|g| {
+            let cost = table.cost(&table.patterns[g], &subset, top_k, depth.saturating_sub(1));
+            (g, cost, g)
+        });
+        let (guess, g) = match self.best_guess(&table.guesses, scored) {
+            Some(best) => best,
+            None => return self.likeliest_candidate(),
+        };
+        self.keep_buckets(&guess, &table.patterns[g]);
+        guess
     }
 
-    fn filter_solutions(game: &Game, gr: &GuessResult, solutions: &mut Vec<String>) {
-        solutions
-            .retain(|solution| gr == &GuessResult::check(&gr.guess, solution, game.letter_count));
+    pub fn enable_profiling(&mut self) {
+        self.profile.replace(Some(Profile::default()));
     }
 
-    fn compute_score_minev(&self, guess: &Word) -> f64 {
-        let mut results: HashMap<GuessResult, usize> = HashMap::new();
-        for solution in &self.possible_solutions {
-            let res = GuessResult::check(guess, solution, self.game.letter_count);
-            let n = results.entry(res).or_insert(0);
-            *n += 1;
+    pub fn profile(&self) -> Option<Profile> {
+        self.profile.borrow().clone()
+    }
+
+    // Adds to the stats of a turn when profiling, including the feedback computations done
+    // since `feedbacks`.
+    fn record_turn<F: FnOnce(&mut TurnStats)>(&self, turn: usize, feedbacks: u64, f: F) {
+        if let Some(profile) = self.profile.borrow_mut().as_mut() {
+            let stats = profile.turn(turn);
+            stats.feedbacks += self.feedbacks.get() - feedbacks;
+            f(stats);
         }
-        results.values().map(|n| (n * n) as f64).sum()
     }
 
-    fn compute_score_minlogev(&self, guess: &Word) -> f64 {
-        let mut results: HashMap<GuessResult, usize> = HashMap::new();
-        for solution in &self.possible_solutions {
-            let res = GuessResult::check(guess, solution, self.game.letter_count);
-            let n = results.entry(res).or_insert(0);
-            *n += 1;
+    fn patterns(&self, guess: &Word) -> Vec<Pattern> {
+        self.feedbacks
+            .set(self.feedbacks.get() + self.solution_words.len() as u64);
+        match (&self.packed_solutions, PackedWord::new(guess)) {
+            (Some(solutions), Some(guess)) => {
+                let mut out = Vec::with_capacity(solutions.len());
+                feedback_batch(&guess, solutions, &mut out);
+                out
+            }
+            _ => self
+                .solution_words
+                .iter()
+                .map(|s| feedback(guess, s))
+                .collect(),
+        }
+    }
+
+    fn filter_solutions(&mut self, gr: &GuessResult) {
+        let (start, feedbacks) = (Instant::now(), self.feedbacks.get());
+        let observed = Pattern::from(gr);
+        // jump to the bucket when the guess is the one suggested, else check every candidate
+        let kept: Vec<usize> = match self.buckets.take() {
+            Some((guess, mut buckets)) if guess == gr.guess => {
+                buckets.remove(&observed).unwrap_or_default()
+            }
+            _ => self
+                .patterns(&gr.guess)
+                .into_iter()
+                .enumerate()
+                .filter(|(_, p)| *p == observed)
+                .map(|(c, _)| c)
+                .collect(),
+        };
+        // the search modes carry on from the observed bucket, as long as the guesses stay the
+        // same
+        let table = match &*self.pattern_table.borrow() {
+            Some(table) if !self.guess_pool.depends_on_candidates() => {
+                Some(Rc::new(table.restrict(&kept)))
+            }
+            _ => None,
+        };
+        self.possible_solutions = kept
+            .iter()
+            .map(|&c| self.possible_solutions[c].clone())
+            .collect();
+        self.weights = kept.iter().map(|&c| self.weights[c]).collect();
+        self.index_solutions();
+        self.pattern_table.replace(table);
+        // the guess is already recorded
+        self.record_turn(self.game.guesses.len(), feedbacks, |stats| {
+            stats.filter += start.elapsed()
+        });
+    }
+
+    // Size and prior mass of each feedback bucket, given the feedback to a guess of each
+    // candidate. Masses are scaled to sum to the number of candidates, so a uniform prior
+    // gives the plain counts.
+    fn partition(&self, row: &[Pattern]) -> HashMap<Pattern, (usize, f64)> {
+        let scale = self.possible_solutions.len() as f64 / self.weights.iter().sum::<f64>();
+        let mut results: HashMap<Pattern, (usize, f64)> = HashMap::new();
+        for (&pattern, weight) in row.iter().zip(&self.weights) {
+            let bucket = results.entry(pattern).or_insert((0, 0.0));
+            bucket.0 += 1;
+            bucket.1 += weight * scale;
         }
         results
+    }
+
+    fn compute_score_minev(&self, row: &[Pattern]) -> f64 {
+        self.partition(row)
+            .values()
+            .map(|(n, m)| *n as f64 * m)
+            .sum()
+    }
+
+    fn compute_score_minlogev(&self, row: &[Pattern]) -> f64 {
+        self.partition(row)
+            .values()
+            .map(|(n, m)| m * (*n as f64).log2())
+            .sum()
+    }
+
+    fn compute_score_minimax(&self, row: &[Pattern]) -> f64 {
+        self.partition(row)
             .values()
-            .map(|n| (*n as f64 * (*n as f64).log2()))
+            .map(|(n, _)| *n)
+            .max()
+            .unwrap_or(0) as f64
+    }
+
+    fn entropy(&self, row: &[Pattern]) -> f64 {
+        let total = self.possible_solutions.len() as f64;
+        self.partition(row)
+            .values()
+            .map(|(_, m)| {
+                let p = m / total;
+                -p * p.log2()
+            })
             .sum()
     }
 
-    fn compute_score_minimax(&self, guess: &Word) -> f64 {
-        let mut results: HashMap<GuessResult, usize> = HashMap::new();
-        for solution in &self.possible_solutions {
-            let res = GuessResult::check(guess, solution, self.game.letter_count);
-            let n = results.entry(res).or_insert(0);
-            *n += 1;
+    // Expected information of the feedback to a guess, in bits.
+    pub fn expected_bits(&self, guess: &Word) -> f64 {
+        self.entropy(&self.patterns(guess))
+    }
+
+    // Score of a guess from its feedback row, in the modes scoring a single partition.
+    // Search modes fall back to MinEV.
+    fn score_row(&self, row: &[Pattern], mode: &SolverMode) -> f64 {
+        match *mode {
+            SolverMode::Minimax => self.compute_score_minimax(row),
+            SolverMode::MinLogEV => self.compute_score_minlogev(row),
+            // scores are minimized
+            SolverMode::MaxEntropy => -self.entropy(row),
+            _ => self.compute_score_minev(row),
+        }
+    }
+
+    fn letter_stats(&self) -> Rc<LetterStats> {
+        if let Some(stats) = &*self.letter_stats.borrow() {
+            return stats.clone();
+        }
+        let stats = Rc::new(LetterStats::new(&self.possible_solutions));
+        self.letter_stats.replace(Some(stats.clone()));
+        stats
+    }
+
+    // Sum over the guess letters of p * (1 - p), p being the share of candidates with the
+    // letter, anywhere and at its position: letters in about half the candidates split them
+    // best. Repeated letters only count at their position, at a discount.
+    fn compute_score_letter_frequency(&self, guess: &Word) -> f64 {
+        let stats = self.letter_stats();
+        let mut seen: Vec<char> = Vec::with_capacity(guess.len());
+        let mut value = 0.0;
+        for (i, letter) in guess.vec.iter().enumerate() {
+            let c = letter.as_char();
+            let exact = stats.position_frequency(i, c);
+            if seen.contains(&c) {
+                value += REPEATED_LETTER_WEIGHT * exact * (1.0 - exact);
+            } else {
+                let anywhere = stats.word_frequency(c);
+                value += exact * (1.0 - exact) + anywhere * (1.0 - anywhere);
+                seen.push(c);
+            }
         }
-        *results.values().max().unwrap() as f64
+        // scores are minimized
+        -value
+    }
+
+    // Largest family of at least `min_size` possible solutions that differ in one letter. Left
+    // alone it can take a guess per member, so it is worth splitting early.
+    pub fn live_family(&self, min_size: usize) -> Option<Family> {
+        families::families(&self.possible_solutions, min_size)
+            .into_iter()
+            .next()
     }
 
     pub fn compute_score(&self, guess: &Word, mode: &SolverMode) -> f64 {
         match *mode {
-            SolverMode::Minimax => self.compute_score_minimax(guess),
-            SolverMode::MinEV => self.compute_score_minev(guess),
-            SolverMode::MinLogEV => self.compute_score_minlogev(guess),
+            SolverMode::Minimax
+            | SolverMode::MinEV
+            | SolverMode::MinLogEV
+            | SolverMode::MaxEntropy => self.score_row(&self.patterns(guess), mode),
+            SolverMode::Lookahead { top_k, depth } => {
+                self.compute_score_lookahead(guess, top_k, depth)
+            }
+            SolverMode::LetterFrequency { exact_below } => {
+                if self.possible_solutions.len() <= exact_below {
+                    self.compute_score_minev(&self.patterns(guess))
+                } else {
+                    self.compute_score_letter_frequency(guess)
+                }
+            }
+            SolverMode::WithinN {
+                guesses,
+                top_k,
+                depth,
+            } => self.compute_score_within(guess, guesses, top_k, depth),
         }
     }
 
+    // Information actually gained when the candidates went from `before` to `after`.
+    pub fn bits_gained(before: usize, after: usize) -> f64 {
+        (before as f64 / after.max(1) as f64).log2()
+    }
+
     fn find_guess(&self, mode: &SolverMode) -> Word {
         if self.possible_solutions.len() == 1 {
-            return (&self.possible_solutions[0][..]).into();
+            return self.word(&self.possible_solutions[0]);
         }
-        let possible_guesses: Vec<Word> = self
-            .game
-            .wordlist
-            .words_by_frequency
-            .iter()
-            .map(|s| (&s[..]).into())
-            .collect();
+        match *mode {
+            SolverMode::Lookahead { top_k, depth } => {
+                return self.find_guess_lookahead(top_k, depth)
+            }
+            SolverMode::WithinN {
+                guesses,
+                top_k,
+                depth,
+            } => return self.find_guess_within(guesses, top_k, depth),
+            _ => {}
+        }
+        let possible_guesses = self.guess_words();
+        if let SolverMode::LetterFrequency { exact_below } = *mode {
+            if self.possible_solutions.len() > exact_below {
+                let scored = possible_guesses
+                    .iter()
+                    .enumerate()
+                    .map(|(g, guess)| (g, self.compute_score_letter_frequency(guess), ()));
+                return match self.best_guess(&possible_guesses, scored) {
+                    Some((guess, ())) => guess,
+                    None => self.likeliest_candidate(),
+                };
+            }
+        }
+        let scored = possible_guesses.iter().enumerate().map(|(g, guess)| {
+            let row = self.patterns(guess);
+            (g, self.score_row(&row, mode), row)
+        });
+        let (guess, row) = match self.best_guess(&possible_guesses, scored) {
+            Some(best) => best,
+            None => return self.likeliest_candidate(),
+        };
+        self.keep_buckets(&guess, &row);
+        guess
+    }
+
+    // Lowest scoring guess, given as indices into `guesses`, and its payload. Only the
+    // payloads of guesses tied for the best score so far are kept. None when nothing was
+    // scored.
+    fn best_guess<T, I>(&self, guesses: &[Word], scored: I) -> Option<(Word, T)>
+    where
+        I: IntoIterator<Item = (usize, f64, T)>,
+    {
+        let mut best_score = f64::INFINITY;
+        let mut tied: Vec<(usize, T)> = vec![];
+        for (g, score, payload) in scored {
+            // NaN scores, e.g. from a prior without mass, rank last
+            let score = if score.is_nan() { f64::INFINITY } else { score };
+            match score.total_cmp(&best_score) {
+                Ordering::Less => {
+                    best_score = score;
+                    tied.clear();
+                    tied.push((g, payload));
+                }
+                Ordering::Equal => tied.push((g, payload)),
+                Ordering::Greater => {}
+            }
+        }
+        if tied.is_empty() {
+            return None;
+        }
+        tied.sort_unstable_by_key(|(g, _)| *g);
+        let words: Vec<Word> = tied.iter().map(|(g, _)| guesses[*g].clone()).collect();
+        let (g, payload) = tied.swap_remove(self.break_tie(&words));
+        Some((guesses[g].clone(), payload))
+    }
 
-        let mut best_guess = possible_guesses[0].clone();
-        let mut best_score = self.compute_score(&best_guess, mode);
+    // Chooses between guesses with the same score, ordered from most to least common.
+    fn break_tie(&self, tied: &[Word]) -> usize {
+        match &self.tie_break {
+            TieBreak::Common => 0,
+            TieBreak::Candidate => tied
+                .iter()
+                .position(|w| self.possible_solutions.contains(&w.to_string()))
+                .unwrap_or(0),
+            TieBreak::Distinct => (0..tied.len())
+                .min_by_key(|&i| {
+                    let distinct: HashSet<_> = tied[i].vec.iter().collect();
+                    tied[i].len() - distinct.len()
+                })
+                .unwrap_or(0),
+            TieBreak::Lexicographic => (0..tied.len())
+                .min_by_key(|&i| tied[i].to_string())
+                .unwrap_or(0),
+            TieBreak::Random(seed) => {
+                let mut rng =
+                    StdRng::seed_from_u64(seed.wrapping_add(self.game.guesses.len() as u64));
+                let indices: Vec<usize> = (0..tied.len()).collect();
+                indices.choose(&mut rng).copied().unwrap_or(0)
+            }
+            TieBreak::Score(mode) => (0..tied.len())
+                .min_by(|&a, &b| {
+                    self.compute_score(&tied[a], mode)
+                        .total_cmp(&self.compute_score(&tied[b], mode))
+                })
+                .unwrap_or(0),
+        }
+    }
 
-        for guess in possible_guesses {
-            let score = self.compute_score(&guess, mode);
-            // favor guess which is a potential solution
-            if score == best_score
-                && !self.possible_solutions.contains(&best_guess.to_string())
-                && self.possible_solutions.contains(&guess.to_string())
-            {
-                best_guess = guess;
-            } else if score < best_score {
-                best_guess = guess;
-                best_score = score;
+    // Pre-computed best first guess, when it applies to this game.
+    pub fn precomputed_first_guess(&self, mode: &SolverMode) -> Option<Word> {
+        match first_guess(mode, &self.game.wordlist.source) {
+            Some(guess) if self.first_guess && matches!(self.guess_pool, GuessPool::All) => {
+                Some(self.word(&guess))
             }
+            _ => None,
         }
-        best_guess
     }
 
-    pub fn guess(&mut self, mode: &SolverMode) {
-        // Pre-computed best first guess
-        let guess: Word = if self.first_guess {
-            self.first_guess = false;
-            match first_guess(mode, &self.game.wordlist.source) {
-                Some(guess) => (&guess[..]).into(),
-                None => self.find_guess(mode),
+    pub fn suggest(&self, mode: &SolverMode) -> Result<Word, Error> {
+        if self.possible_solutions.is_empty() {
+            return Err(Error::NoSolutions);
+        }
+        let (start, feedbacks) = (Instant::now(), self.feedbacks.get());
+        let guess = match (&self.opener, self.precomputed_first_guess(mode)) {
+            (Some(opener), _) if self.game.guesses.is_empty() => opener.clone(),
+            (_, Some(guess)) => guess,
+            _ => self.find_guess(mode),
+        };
+        self.record_turn(self.game.guesses.len() + 1, feedbacks, |stats| {
+            stats.count += 1;
+            stats.find_guess += start.elapsed();
+        });
+        Ok(guess)
+    }
+
+    pub fn guess(&mut self, mode: &SolverMode) -> Result<GuessResult, Error> {
+        let guess = self.suggest(mode)?;
+        self.first_guess = false;
+        let res = self.game.guess(guess.to_string())?;
+        self.filter_solutions(&res);
+        Ok(res)
+    }
+
+    pub fn observe(&mut self, result: GuessResult) -> Result<(), Error> {
+        self.game.record(result.clone())?;
+        self.first_guess = false;
+        self.filter_solutions(&result);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::alphabet::Alphabet;
+    use crate::game::State;
+    use crate::words::WordList;
+
+    fn w(s: &str) -> Word {
+        Alphabet::english().word(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_lookahead() {
+        assert!(matches!(
+            "lookahead".parse(),
+            Ok(SolverMode::Lookahead {
+                top_k: 10,
+                depth: 2
+            })
+        ));
+        assert!(matches!(
+            "Lookahead,5,3".parse(),
+            Ok(SolverMode::Lookahead { top_k: 5, depth: 3 })
+        ));
+        assert!("lookahead,0".parse::<SolverMode>().is_err());
+        assert!("lookaheadx".parse::<SolverMode>().is_err());
+    }
+
+    #[test]
+    fn test_tie_break() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        let mut solver = Solver::new(&mut game);
+        let words = |list: &[&str]| -> Vec<Word> { list.iter().map(|s| w(s)).collect() };
+        let tied = words(&["aahed", "sassy", "cigar"]);
+        let mut pick = |tie_break: &str, tied: &[Word]| {
+            solver.tie_break = tie_break.parse().unwrap();
+            tied[solver.break_tie(tied)].to_string()
+        };
+        assert_eq!("sassy", pick("candidate", &tied));
+        assert_eq!("aahed", pick("common", &tied));
+        assert_eq!("cigar", pick("distinct", &tied));
+        assert_eq!("aahed", pick("lexicographic", &words(&["cigar", "aahed"])));
+        assert_eq!(pick("random:7", &tied), pick("random:7", &tied));
+        assert!("score".parse::<TieBreak>().is_err());
+        assert!("score:minimax".parse::<TieBreak>().is_ok());
+    }
+
+    #[test]
+    fn test_best_guess_nan() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        let solver = Solver::new(&mut game);
+        let guesses = vec![w("cigar"), w("rebut")];
+        let best = |scores: &[f64]| {
+            let scored = scores.iter().enumerate().map(|(g, s)| (g, *s, ()));
+            solver
+                .best_guess(&guesses, scored)
+                .map(|(w, _)| w.to_string())
+        };
+        assert_eq!(Some("rebut".to_string()), best(&[f64::NAN, 2.0]));
+        assert_eq!(Some("cigar".to_string()), best(&[f64::NAN, -f64::NAN]));
+        assert_eq!(None, best(&[]));
+        // a prior without mass scores every guess NaN
+        game.set_solution("cigar".to_string()).unwrap();
+        game.guess("roate".to_string()).unwrap();
+        let mut solver = Solver::new(&mut game);
+        solver.weights = vec![0.0; solver.weights.len()];
+        let guess = solver.suggest(&SolverMode::MinEV).unwrap();
+        assert!(solver.possible_solutions.contains(&guess.to_string()));
+    }
+
+    #[test]
+    fn test_guess_pool() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("cigar".to_string()).unwrap();
+        game.guess("roate".to_string()).unwrap();
+        let mut solver = Solver::new(&mut game);
+        solver.guess_pool = "candidates".parse().unwrap();
+        assert_eq!(solver.possible_solutions.len(), solver.guess_words().len());
+        let guess = solver.suggest(&SolverMode::MinEV).unwrap();
+        assert!(solver.possible_solutions.contains(&guess.to_string()));
+        solver.guess_pool = "top,100".parse().unwrap();
+        let pool: Vec<String> = solver.guess_words().iter().map(String::from).collect();
+        // the candidates outside the top 100 are added, in frequency order
+        let n = solver.possible_solutions.len();
+        let outside = solver
+            .possible_solutions
+            .iter()
+            .filter(|s| !pool[..100].contains(s))
+            .count();
+        assert!(outside > 0 && outside < n);
+        assert_eq!(100 + outside, pool.len());
+        assert!("top,0".parse::<GuessPool>().is_err());
+        assert!("some".parse::<GuessPool>().is_err());
+    }
+
+    #[test]
+    fn test_top_pool_uncommon_solution() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("vaunt".to_string()).unwrap();
+        let mut solver = Solver::new(&mut game);
+        solver.guess_pool = GuessPool::Top(10);
+        assert!(!solver.guess_words()[..10].contains(&w("vaunt")));
+        while let State::Unsolved = solver.game.state() {
+            solver.guess(&SolverMode::MinEV).unwrap();
+        }
+        assert!(matches!(solver.game.state(), State::Solved));
+    }
+
+    #[test]
+    fn test_profile() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("cigar".to_string()).unwrap();
+        let mut solver = Solver::new(&mut game);
+        assert!(solver.profile().is_none());
+        solver.enable_profiling();
+        while let State::Unsolved = solver.game.state() {
+            solver.guess(&SolverMode::MinEV).unwrap();
+        }
+        let profile = solver.profile().unwrap();
+        assert_eq!(solver.game.guesses.len(), profile.turns.len());
+        assert!(profile.turns.iter().all(|t| t.count == 1));
+        // the first guess is precomputed, only filtering checks the solutions
+        assert_eq!(2315, profile.turns[0].feedbacks);
+        assert!(profile.turns[1].feedbacks > 12972);
+    }
+
+    #[test]
+    fn test_parse_within() {
+        assert!(matches!(
+            "within,3".parse(),
+            Ok(SolverMode::WithinN {
+                guesses: 3,
+                top_k: 10,
+                depth: 1
+            })
+        ));
+        assert!("within".parse::<SolverMode>().is_err());
+        assert!("within,0".parse::<SolverMode>().is_err());
+    }
+
+    #[test]
+    fn test_within_last_guess() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("cigar".to_string()).unwrap();
+        game.guess("roate".to_string()).unwrap();
+        let solver = Solver::new(&mut game);
+        let n = solver.possible_solutions.len() as f64;
+        // with one guess left only a candidate can win
+        let mode = SolverMode::WithinN {
+            guesses: 2,
+            top_k: 10,
+            depth: 1,
+        };
+        let guess = solver.suggest(&mode).unwrap();
+        assert!(solver.possible_solutions.contains(&guess.to_string()));
+        let p = -solver.compute_score(&guess, &mode);
+        assert!((p - 1.0 / n).abs() < 1e-9);
+        let roate = solver.word("roate");
+        assert_eq!(0.0, -solver.compute_score(&roate, &mode));
+    }
+
+    #[test]
+    fn test_within_last_guess_outside_top() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("cigar".to_string()).unwrap();
+        game.guess("roate".to_string()).unwrap();
+        let solver = Solver::new(&mut game);
+        // the single top guess is not a candidate, and could not win on the last try
+        let best = solver.suggest(&SolverMode::MinEV).unwrap();
+        assert!(!solver.possible_solutions.contains(&best.to_string()));
+        let mode = SolverMode::WithinN {
+            guesses: 2,
+            top_k: 1,
+            depth: 1,
+        };
+        let guess = solver.suggest(&mode).unwrap();
+        assert!(solver.possible_solutions.contains(&guess.to_string()));
+    }
+
+    #[test]
+    fn test_zero_search_params() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("cigar".to_string()).unwrap();
+        game.guess("roate".to_string()).unwrap();
+        let solver = Solver::new(&mut game);
+        // FromStr rejects these, but the enum can be built directly
+        let modes = [
+            SolverMode::Lookahead { top_k: 0, depth: 0 },
+            SolverMode::WithinN {
+                guesses: 0,
+                top_k: 0,
+                depth: 0,
+            },
+            SolverMode::WithinN {
+                guesses: 4,
+                top_k: 0,
+                depth: 0,
+            },
+        ];
+        for mode in &modes {
+            let guess = solver.suggest(mode).unwrap();
+            assert!(solver.compute_score(&guess, mode).is_finite());
+        }
+    }
+
+    #[test]
+    fn test_lookahead_solves() {
+        let mode = SolverMode::Lookahead { top_k: 5, depth: 2 };
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("cigar".to_string()).unwrap();
+        let mut solver = Solver::new(&mut game);
+        solver.guess(&mode).unwrap();
+        let after_first = solver.possible_solutions.len();
+        let guess = solver.suggest(&mode).unwrap();
+        // the expected number of guesses is at least one, and one only for the last candidate
+        assert!(solver.compute_score(&guess, &mode) > 1.0);
+        while let State::Unsolved = solver.game.state() {
+            solver.guess(&mode).unwrap();
+        }
+        assert!(matches!(solver.game.state(), State::Solved));
+        assert!(after_first > 1);
+    }
+
+    #[test]
+    fn test_prior_first_guess() {
+        let source: WordSource = "scrabble,5,300".parse().unwrap();
+        let mut game = Game::from_source(&source).unwrap();
+        let uniform = Solver::new(&mut game).suggest(&SolverMode::MinEV).unwrap();
+        assert_eq!(w("tares"), uniform);
+        // common words weigh more, the uniform opener no longer applies
+        let prior: Prior = "frequency,50,10".parse().unwrap();
+        let solver = Solver::with_prior(&mut game, &prior);
+        assert!(solver.precomputed_first_guess(&SolverMode::MinEV).is_none());
+        assert_ne!(uniform, solver.suggest(&SolverMode::MinEV).unwrap());
+    }
+
+    #[test]
+    fn test_restricted_table() {
+        let modes = [
+            SolverMode::Lookahead { top_k: 2, depth: 2 },
+            SolverMode::WithinN {
+                guesses: 4,
+                top_k: 2,
+                depth: 1,
+            },
+        ];
+        for mode in &modes {
+            let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+            game.set_solution("vaunt".to_string()).unwrap();
+            let mut solver = Solver::new(&mut game);
+            while let State::Unsolved = solver.game.state() {
+                solver.guess(mode).unwrap();
+            }
+            let played: Vec<Word> = game.guesses.iter().map(|g| g.guess.clone()).collect();
+            // a new solver each turn builds its table from scratch
+            let mut fresh = Game::from_source(&WordSource::Wordle).unwrap();
+            fresh.set_solution("vaunt".to_string()).unwrap();
+            for guess in played {
+                assert_eq!(guess, Solver::new(&mut fresh).suggest(mode).unwrap());
+                fresh.guess(guess.to_string()).unwrap();
             }
-        } else {
-            self.find_guess(mode)
+        }
+    }
+
+    #[test]
+    fn test_lengths_opener() {
+        let source: WordSource = "scrabble,5,100".parse().unwrap();
+        let lengths = "4-5".parse().unwrap();
+        let wordlists: Vec<Rc<WordList>> = WordList::from_source_lengths(&source, lengths)
+            .unwrap()
+            .into_iter()
+            .map(Rc::new)
+            .collect();
+        let mut game = Game::from_wordlist_seeded(wordlists[0].clone(), 1).unwrap();
+        let mut openers = vec![];
+        for wordlist in &wordlists {
+            game.set_wordlist(wordlist.clone());
+            let solver = Solver::new(&mut game);
+            // no precomputed first guess for 4 letters
+            let precomputed = solver.precomputed_first_guess(&SolverMode::MinEV);
+            assert_eq!(solver.game.letter_count == 5, precomputed.is_some());
+            let opener = solver.suggest(&SolverMode::MinEV).unwrap();
+            assert_eq!(solver.game.letter_count, opener.len());
+            openers.push(opener);
+        }
+        assert_ne!(openers[0].len(), openers[1].len());
+        // the opener wins over the precomputed first guess
+        let mut lengths = HashSet::new();
+        for _ in 0..6 {
+            game.pick_wordlist(&wordlists).unwrap();
+            assert_eq!(game.letter_count, game.solution.chars().count());
+            lengths.insert(game.letter_count);
+            let opener = match game.letter_count {
+                4 => openers[0].clone(),
+                _ => w("crane"),
+            };
+            let mut solver = Solver::new(&mut game);
+            solver.opener = Some(opener.clone());
+            assert_eq!(opener, solver.guess(&SolverMode::MinEV).unwrap().guess);
+        }
+        assert_eq!(2, lengths.len());
+    }
+
+    #[test]
+    fn test_letter_frequency() {
+        assert!(matches!(
+            "letterfreq".parse(),
+            Ok(SolverMode::LetterFrequency { exact_below: 100 })
+        ));
+        assert!("letterfreq,x".parse::<SolverMode>().is_err());
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("cigar".to_string()).unwrap();
+        let solver = Solver::new(&mut game);
+        let mode = SolverMode::LetterFrequency { exact_below: 0 };
+        // a repeated letter is worth less than a new one
+        let (later, alter) = (solver.word("later"), solver.word("aller"));
+        assert!(solver.compute_score(&later, &mode) < solver.compute_score(&alter, &mode));
+        let exact = SolverMode::LetterFrequency {
+            exact_below: solver.possible_solutions.len(),
         };
-        let res = self.game.guess(guess.to_string()).unwrap();
-        Self::filter_solutions(self.game, &res, &mut self.possible_solutions);
+        assert_eq!(
+            solver.compute_score(&later, &SolverMode::MinEV),
+            solver.compute_score(&later, &exact)
+        );
+    }
+
+    #[test]
+    fn test_filter_from_buckets() {
+        let mut game = Game::from_source(&WordSource::Wordle).unwrap();
+        game.set_solution("cigar".to_string()).unwrap();
+        let mut solver = Solver::new(&mut game);
+        solver.first_guess = false;
+        solver.guess_pool = GuessPool::Top(500);
+        let pool = solver.guess_words().len() as u64;
+        solver.enable_profiling();
+        solver.guess(&SolverMode::MinEV).unwrap();
+        // the observed bucket comes from scoring, filtering checks no feedback
+        assert_eq!(pool * 2315, solver.profile().unwrap().turns[0].feedbacks);
+        let mut kept = solver.possible_solutions.clone();
+        kept.sort();
+        // replaying the guess checks every candidate, for the same result
+        let mut replayed = Solver::new(&mut game).possible_solutions;
+        replayed.sort();
+        assert_eq!(kept, replayed);
     }
 }
@@ -0,0 +1,130 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::words::WordList;
+
+// Letter statistics of a set of words.
+#[derive(Debug, Clone, Default)]
+pub struct LetterStats {
+    pub words: usize,
+    // occurrences of each letter
+    pub letters: BTreeMap<char, usize>,
+    // words containing each letter
+    pub containing: BTreeMap<char, usize>,
+    // occurrences of each letter at each position
+    pub positions: Vec<BTreeMap<char, usize>>,
+    // occurrences of each pair of adjacent letters
+    pub bigrams: BTreeMap<String, usize>,
+    // words using a letter more than once
+    pub repeated: usize,
+    // words with the same letter twice in a row, e.g. "apple"
+    pub doubles: usize,
+}
+
+impl LetterStats {
+    pub fn new<S: AsRef<str>>(words: &[S]) -> Self {
+        let mut stats = Self::default();
+        for word in words {
+            stats.add(word.as_ref());
+        }
+        stats
+    }
+    pub fn solutions(wordlist: &WordList) -> Self {
+        let solutions: Vec<&String> = wordlist.allowed_solutions.iter().collect();
+        Self::new(&solutions)
+    }
+    pub fn guesses(wordlist: &WordList) -> Self {
+        Self::new(&wordlist.words_by_frequency)
+    }
+    fn add(&mut self, word: &str) {
+        let chars: Vec<char> = word.chars().collect();
+        self.words += 1;
+        if self.positions.len() < chars.len() {
+            self.positions.resize(chars.len(), BTreeMap::new());
+        }
+        for (i, c) in chars.iter().enumerate() {
+            *self.letters.entry(*c).or_insert(0) += 1;
+            *self.positions[i].entry(*c).or_insert(0) += 1;
+        }
+        let distinct: HashSet<&char> = chars.iter().collect();
+        for c in &distinct {
+            *self.containing.entry(**c).or_insert(0) += 1;
+        }
+        if distinct.len() < chars.len() {
+            self.repeated += 1;
+        }
+        for pair in chars.windows(2) {
+            *self.bigrams.entry(pair.iter().collect()).or_insert(0) += 1;
+        }
+        if chars.windows(2).any(|pair| pair[0] == pair[1]) {
+            self.doubles += 1;
+        }
+    }
+    pub fn total_letters(&self) -> usize {
+        self.letters.values().sum()
+    }
+    // Share of all letters that are `c`.
+    pub fn letter_frequency(&self, c: char) -> f64 {
+        share(self.letters.get(&c), self.total_letters())
+    }
+    // Share of words containing `c`.
+    pub fn word_frequency(&self, c: char) -> f64 {
+        share(self.containing.get(&c), self.words)
+    }
+    // Share of words with `c` at `position`, counting from 0.
+    pub fn position_frequency(&self, position: usize, c: char) -> f64 {
+        let count = self.positions.get(position).and_then(|p| p.get(&c));
+        share(count, self.words)
+    }
+    pub fn bigram_frequency(&self, bigram: &str) -> f64 {
+        share(self.bigrams.get(bigram), self.bigrams.values().sum())
+    }
+    pub fn top_letters(&self, n: usize) -> Vec<(char, usize)> {
+        top(&self.letters, n)
+    }
+    pub fn top_at(&self, position: usize, n: usize) -> Vec<(char, usize)> {
+        self.positions
+            .get(position)
+            .map(|p| top(p, n))
+            .unwrap_or_default()
+    }
+    pub fn top_bigrams(&self, n: usize) -> Vec<(String, usize)> {
+        top(&self.bigrams, n)
+    }
+}
+
+fn share(count: Option<&usize>, total: usize) -> f64 {
+    match total {
+        0 => 0.0,
+        total => *count.unwrap_or(&0) as f64 / total as f64,
+    }
+}
+
+// The `n` most common keys, ties in key order.
+fn top<K: Clone + Ord>(map: &BTreeMap<K, usize>, n: usize) -> Vec<(K, usize)> {
+    let mut entries: Vec<(K, usize)> = map.iter().map(|(k, v)| (k.clone(), *v)).collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    entries.truncate(n);
+    entries
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_letter_stats() {
+        let stats = LetterStats::new(&["apple", "plate", "crate", "eerie"]);
+        assert_eq!(4, stats.words);
+        assert_eq!(20, stats.total_letters());
+        assert_eq!(vec![('e', 6), ('a', 3)], stats.top_letters(2));
+        assert!((stats.letter_frequency('e') - 0.3).abs() < 1e-9);
+        assert!((stats.word_frequency('e') - 1.0).abs() < 1e-9);
+        assert!((stats.position_frequency(4, 'e') - 1.0).abs() < 1e-9);
+        assert_eq!(0.0, stats.position_frequency(7, 'e'));
+        assert_eq!(vec![('a', 1), ('c', 1)], stats.top_at(0, 2));
+        assert_eq!(vec![("at".to_string(), 2)], stats.top_bigrams(1));
+        assert!((stats.bigram_frequency("te") - 2.0 / 16.0).abs() < 1e-9);
+        assert_eq!(2, stats.repeated);
+        assert_eq!(2, stats.doubles);
+    }
+}
@@ -1,35 +1,59 @@
 use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use lazy_static::lazy_static;
 
+use crate::alphabet::Alphabet;
+use crate::error::Error;
+
 const SCRABBLE_WORDS: &str = include_str!("data/scrabble.txt");
 const DICTIONARY_WORDS: &str = include_str!("data/dictionary.txt");
 const WORDLE_WORDS: &str = include_str!("data/wordle.txt");
 const WORDS_FREQUENCY: &str = include_str!("data/frequency.txt");
 
+// wordle.txt starts with the official answers, in puzzle order
+const WORDLE_ANSWERS: usize = 2315;
+
 lazy_static! {
-    static ref WORD_FREQUENCIES: HashMap<String, u64> = {
-        let mut map = HashMap::new();
-        for wordfreq in WORDS_FREQUENCY.split('\n') {
-            let parts: Vec<&str> = wordfreq.split('\t').collect();
-            if parts.len() == 2 {
-                let word = parts[0];
-                let freq: u64 = parts[1].parse().unwrap();
-                map.insert(word.to_string(), freq);
-            } else {
-                panic!("bad frequency word list");
-            }
+    static ref WORD_FREQUENCIES: Result<HashMap<String, u64>, String> =
+        parse_frequencies(WORDS_FREQUENCY);
+}
+
+fn parse_frequencies(list: &str) -> Result<HashMap<String, u64>, String> {
+    let mut map = HashMap::new();
+    for (i, wordfreq) in list.split('\n').enumerate() {
+        let parts: Vec<&str> = wordfreq.split('\t').collect();
+        if parts.len() != 2 {
+            return Err(format!("expected word and frequency on line {}", i + 1));
         }
-        map
-    };
+        let freq: u64 = match parts[1].parse() {
+            Ok(freq) => freq,
+            Err(e) => return Err(format!("{} on line {}", e, i + 1)),
+        };
+        map.insert(parts[0].to_string(), freq);
+    }
+    Ok(map)
 }
 
 #[derive(Debug, Clone)]
 pub enum WordSource {
     Wordle,
-    Scrabble { letter_count: usize, top_n: usize },
-    Dictionary { letter_count: usize, top_n: usize },
+    Scrabble {
+        letter_count: usize,
+        top_n: usize,
+    },
+    Dictionary {
+        letter_count: usize,
+        top_n: usize,
+    },
+    File {
+        path: PathBuf,
+        alphabet: Alphabet,
+        letter_count: usize,
+        top_n: usize,
+    },
 }
 
 impl WordSource {
@@ -38,7 +62,58 @@ impl WordSource {
             Self::Wordle => 5,
             Self::Scrabble { letter_count, .. } => *letter_count,
             Self::Dictionary { letter_count, .. } => *letter_count,
+            Self::File { letter_count, .. } => *letter_count,
+        }
+    }
+    pub fn alphabet(&self) -> Alphabet {
+        match self {
+            Self::File { alphabet, .. } => *alphabet,
+            _ => Alphabet::english(),
+        }
+    }
+    // The same source with words of another length.
+    pub fn with_letter_count(&self, n: usize) -> Result<Self, Error> {
+        let mut source = self.clone();
+        match &mut source {
+            Self::Wordle if n == 5 => {}
+            Self::Wordle => {
+                return Err(Error::BadWordList(format!(
+                    "wordle has no {}-letter words",
+                    n
+                )))
+            }
+            Self::Scrabble { letter_count, .. }
+            | Self::Dictionary { letter_count, .. }
+            | Self::File { letter_count, .. } => *letter_count = n,
+        }
+        Ok(source)
+    }
+}
+
+// Range of word lengths, e.g. "4-11", for games where the length of the hidden word is
+// revealed but differs between games.
+#[derive(Debug, Clone, Copy)]
+pub struct Lengths {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl Lengths {
+    pub fn iter(&self) -> impl Iterator<Item = usize> {
+        self.min..=self.max
+    }
+}
+
+impl FromStr for Lengths {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let (min, max) = s.split_once('-').unwrap_or((s, s));
+        let min: usize = min.parse().map_err(|e| format!("{}", e))?;
+        let max: usize = max.parse().map_err(|e| format!("{}", e))?;
+        if min == 0 || min > max {
+            return Err("lengths must be MIN-MAX, with 0 < MIN <= MAX".to_string());
         }
+        Ok(Self { min, max })
     }
 }
 
@@ -48,18 +123,27 @@ impl FromStr for WordSource {
         if s == "wordle" {
             return Ok(WordSource::Wordle);
         }
-        let parts: Vec<&str> = s.split(',').collect();
+        // file paths may hold commas, so the other parts are read from the right
+        let (s, alphabet) = match s.rsplit_once(',') {
+            Some((rest, alphabet))
+                if s.starts_with("file:") && alphabet.parse::<usize>().is_err() =>
+            {
+                (rest, Some(alphabet))
+            }
+            _ => (s, None),
+        };
+        let parts: Vec<&str> = s.rsplitn(3, ',').collect();
         if parts.len() != 3 {
             return Err("invalid word source".to_string());
         }
-        let source = parts[0];
+        let source = parts[2];
         let letter_count: usize = match parts[1].parse() {
             Ok(n) => n,
             Err(e) => {
                 return Err(e.to_string());
             }
         };
-        let top_n: usize = match parts[2].parse() {
+        let top_n: usize = match parts[0].parse() {
             Ok(n) => n,
             Err(e) => {
                 return Err(e.to_string());
@@ -77,46 +161,157 @@ impl FromStr for WordSource {
                 top_n,
             });
         }
+        if let Some(path) = source.strip_prefix("file:") {
+            let alphabet = match alphabet {
+                Some(alphabet) => alphabet.parse()?,
+                None => Alphabet::english(),
+            };
+            return Ok(WordSource::File {
+                path: path.into(),
+                alphabet,
+                letter_count,
+                top_n,
+            });
+        }
         Err("invalid word source".to_string())
     }
 }
 
 pub struct WordList {
     pub source: WordSource,
+    pub alphabet: Alphabet,
+    pub answers: Vec<String>,
     pub allowed_solutions: HashSet<String>,
     pub words: HashSet<String>,
     pub words_by_frequency: Vec<String>,
 }
 
 impl WordList {
-    fn new<T: AsRef<str>>(words: &[T], allow_top_n_solutions: usize, source: WordSource) -> Self {
-        let mut words_by_frequency: Vec<String> =
-            words.iter().map(|v| v.as_ref().to_string()).collect();
-        sort_by_frequency(&mut words_by_frequency);
+    fn new(words_by_frequency: Vec<String>, answers: Vec<String>, source: WordSource) -> Self {
         let words: HashSet<String> = words_by_frequency.iter().cloned().collect();
-        let allowed_solutions: HashSet<String> = words_by_frequency[..allow_top_n_solutions]
-            .iter()
-            .cloned()
-            .collect();
+        let allowed_solutions: HashSet<String> = answers.iter().cloned().collect();
         Self {
+            alphabet: source.alphabet(),
             source,
             words,
             words_by_frequency,
+            answers,
             allowed_solutions,
         }
     }
-    pub fn from_source(source: &WordSource) -> Self {
-        match source {
-            WordSource::Wordle => Self::new(&wordle_words(), 2315, source.clone()),
+    fn from_top_n(words_by_frequency: Vec<String>, top_n: usize, source: WordSource) -> Self {
+        let answers = words_by_frequency[..top_n.min(words_by_frequency.len())].to_vec();
+        Self::new(words_by_frequency, answers, source)
+    }
+    pub fn from_source(source: &WordSource) -> Result<Self, Error> {
+        Ok(match source {
+            WordSource::Wordle => {
+                let words = wordle_words();
+                let answers = words[..WORDLE_ANSWERS].to_vec();
+                Self::new(sort_by_frequency(words)?, answers, source.clone())
+            }
             WordSource::Scrabble {
                 letter_count,
                 top_n,
-            } => Self::new(&scrabble_words(*letter_count), *top_n, source.clone()),
+            } => Self::from_top_n(
+                sort_by_frequency(scrabble_words(*letter_count))?,
+                *top_n,
+                source.clone(),
+            ),
             WordSource::Dictionary {
                 letter_count,
                 top_n,
-            } => Self::new(&dictionary_words(*letter_count), *top_n, source.clone()),
+            } => Self::from_top_n(
+                sort_by_frequency(dictionary_words(*letter_count))?,
+                *top_n,
+                source.clone(),
+            ),
+            // word files are expected to be ordered from most to least common
+            WordSource::File {
+                path,
+                alphabet,
+                letter_count,
+                top_n,
+            } => Self::from_top_n(
+                dedup(get_words(
+                    &fs::read_to_string(path)?,
+                    *letter_count,
+                    alphabet,
+                )),
+                *top_n,
+                source.clone(),
+            ),
+        })
+    }
+    // One word list per length, skipping lengths without solutions.
+    pub fn from_source_lengths(source: &WordSource, lengths: Lengths) -> Result<Vec<Self>, Error> {
+        let mut wordlists = Vec::new();
+        for n in lengths.iter() {
+            let wordlist = Self::from_source(&source.with_letter_count(n)?)?;
+            if !wordlist.allowed_solutions.is_empty() {
+                wordlists.push(wordlist);
+            }
+        }
+        match wordlists.is_empty() {
+            true => Err(Error::NoSolutions),
+            false => Ok(wordlists),
+        }
+    }
+    // Allows extra guesses, ranked after every other word. Returns the number of new words.
+    pub fn add_guesses(&mut self, words: Vec<String>) -> usize {
+        let mut added = 0;
+        for word in words {
+            if self.words.insert(word.clone()) {
+                self.words_by_frequency.push(word);
+                added += 1;
+            }
+        }
+        added
+    }
+    // Reads extra guesses from a file, one per line, skipping words of the wrong length.
+    pub fn add_guesses_from_file(&mut self, path: &Path) -> Result<usize, Error> {
+        let words = get_words(
+            &fs::read_to_string(path)?,
+            self.source.letter_count(),
+            &self.alphabet,
+        );
+        Ok(self.add_guesses(words))
+    }
+    // Answer of a puzzle. Wordle answers are in the original puzzle order, which later
+    // puzzles stopped following; other sources number their answers from most to least
+    // common.
+    pub fn puzzle(&self, number: usize) -> Option<&str> {
+        self.answers.get(number).map(|a| &a[..])
+    }
+    // Answers do not repeat, so the answers of the puzzles before `number` can be ruled out.
+    // `answers` keeps every answer so that puzzle numbers still hold. Returns the number of
+    // solutions removed. Only Wordle answers are in puzzle order.
+    pub fn exclude_before(&mut self, number: usize) -> Result<usize, Error> {
+        if !matches!(self.source, WordSource::Wordle) {
+            return Err(Error::BadWordList(
+                "only wordle answers have puzzle numbers".to_string(),
+            ));
         }
+        if number >= self.answers.len() {
+            return Err(Error::InvalidPuzzle(number));
+        }
+        let used: Vec<String> = self.answers.iter().take(number).cloned().collect();
+        Ok(self.exclude_solutions(&used))
+    }
+    pub fn exclude_solutions(&mut self, words: &[String]) -> usize {
+        words
+            .iter()
+            .filter(|w| self.allowed_solutions.remove(*w))
+            .count()
+    }
+    // Reads used answers from a file, one per line, e.g. a history of past puzzles.
+    pub fn exclude_solutions_from_file(&mut self, path: &Path) -> Result<usize, Error> {
+        let words = get_words(
+            &fs::read_to_string(path)?,
+            self.source.letter_count(),
+            &self.alphabet,
+        );
+        Ok(self.exclude_solutions(&words))
     }
     pub fn is_valid_guess(&self, word: &str) -> bool {
         self.words.contains(word)
@@ -126,43 +321,155 @@ impl WordList {
     }
 }
 
-fn is_valid_word(word: &str, letter_count: usize) -> bool {
-    if word.len() != letter_count {
-        return false;
-    }
-    for c in word.chars() {
-        if !('a'..='z').contains(&c) {
-            return false;
-        }
-    }
-    true
-}
-
-fn get_words(wordlist: &str, letter_count: usize) -> Vec<String> {
+fn get_words(wordlist: &str, letter_count: usize, alphabet: &Alphabet) -> Vec<String> {
     wordlist
         .split('\n')
-        .filter(|w| is_valid_word(w, letter_count))
+        .filter_map(|w| alphabet.word(w.trim()).ok())
+        .filter(|w| w.len() == letter_count)
         .map(|w| w.to_string())
         .collect()
 }
 
 fn scrabble_words(letter_count: usize) -> Vec<String> {
-    get_words(SCRABBLE_WORDS, letter_count)
+    get_words(SCRABBLE_WORDS, letter_count, &Alphabet::english())
 }
 
 fn dictionary_words(letter_count: usize) -> Vec<String> {
-    get_words(DICTIONARY_WORDS, letter_count)
+    get_words(DICTIONARY_WORDS, letter_count, &Alphabet::english())
 }
 
 fn wordle_words() -> Vec<String> {
-    get_words(WORDLE_WORDS, 5)
+    get_words(WORDLE_WORDS, 5, &Alphabet::english())
+}
+
+fn word_frequencies() -> Result<&'static HashMap<String, u64>, Error> {
+    WORD_FREQUENCIES
+        .as_ref()
+        .map_err(|e| Error::BadWordList(e.clone()))
 }
 
-fn word_frequency(word: &str) -> u64 {
-    *WORD_FREQUENCIES.get(word).unwrap_or(&0)
+fn sort_by_frequency(mut words: Vec<String>) -> Result<Vec<String>, Error> {
+    let frequencies = word_frequencies()?;
+    words.sort_by_key(|k| *frequencies.get(k).unwrap_or(&0));
+    words.reverse();
+    Ok(words)
 }
 
-fn sort_by_frequency(words: &mut Vec<String>) {
-    words.sort_by_key(|k| word_frequency(k));
-    words.reverse()
+fn dedup(words: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    words
+        .into_iter()
+        .filter(|w| seen.insert(w.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // A file unique to the test run and test, removed even when the test fails.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(test: &str, contents: &str) -> Self {
+            let name = format!("wordsmith-{}-{}.txt", std::process::id(), test);
+            let path = std::env::temp_dir().join(name);
+            fs::write(&path, contents).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_file_source_with_alphabet() {
+        let file = TempFile::new("spanish", "Árbol\nseñor\narbol\ncasas\nniño\nxx\n");
+        let source: WordSource = format!("file:{},5,2,spanish", file.0.display())
+            .parse()
+            .unwrap();
+        let wordlist = WordList::from_source(&source).unwrap();
+        assert_eq!(vec!["arbol", "señor", "casas"], wordlist.words_by_frequency);
+        assert!(wordlist.is_valid_solution("señor"));
+        assert!(!wordlist.is_valid_solution("casas"));
+    }
+
+    #[test]
+    fn test_file_source_with_commas() {
+        let source: WordSource = "file:/tmp/a,b.txt,6,100,french".parse().unwrap();
+        assert!(matches!(
+            source,
+            WordSource::File { ref path, letter_count: 6, top_n: 100, .. }
+                if path == Path::new("/tmp/a,b.txt")
+        ));
+        let source: WordSource = "file:a,b,5,10".parse().unwrap();
+        assert!(matches!(source, WordSource::File { ref path, .. } if path == Path::new("a,b")));
+        assert!("scrabble,x,5,10".parse::<WordSource>().is_err());
+        assert!("file:a.txt,5".parse::<WordSource>().is_err());
+    }
+
+    #[test]
+    fn test_add_guesses() {
+        let mut wordlist = WordList::from_source(&WordSource::Wordle).unwrap();
+        let n = wordlist.words_by_frequency.len();
+        let file = TempFile::new("extra", "zzzzz\ncigar\ntoolong\n");
+        assert_eq!(1, wordlist.add_guesses_from_file(&file.0).unwrap());
+        assert!(wordlist.is_valid_guess("zzzzz"));
+        assert!(!wordlist.is_valid_solution("zzzzz"));
+        assert_eq!(
+            Some(&"zzzzz".to_string()),
+            wordlist.words_by_frequency.get(n)
+        );
+    }
+
+    #[test]
+    fn test_parse_frequencies() {
+        let map = parse_frequencies("the\t100\nof\t50").unwrap();
+        assert_eq!(Some(&50), map.get("of"));
+        assert!(parse_frequencies("the\t100\nof").is_err());
+        assert!(parse_frequencies("the\tmany").is_err());
+    }
+
+    #[test]
+    fn test_exclude_used() {
+        let mut wordlist = WordList::from_source(&WordSource::Wordle).unwrap();
+        assert_eq!(Some("rebus"), wordlist.puzzle(196));
+        assert_eq!(196, wordlist.exclude_before(196).unwrap());
+        assert!(matches!(
+            wordlist.exclude_before(2315),
+            Err(Error::InvalidPuzzle(2315))
+        ));
+        let mut scrabble = WordList::from_source(&"scrabble,5,100".parse().unwrap()).unwrap();
+        assert!(scrabble.exclude_before(10).is_err());
+        assert_eq!(100, scrabble.allowed_solutions.len());
+        assert!(!wordlist.is_valid_solution("cigar"));
+        assert!(wordlist.is_valid_solution("rebus"));
+        assert!(wordlist.is_valid_guess("cigar"));
+        assert_eq!(Some("cigar"), wordlist.puzzle(0));
+        let file = TempFile::new("used", "rebus\ncigar\n");
+        assert_eq!(1, wordlist.exclude_solutions_from_file(&file.0).unwrap());
+        assert_eq!(2315 - 197, wordlist.allowed_solutions.len());
+    }
+
+    #[test]
+    fn test_lengths() {
+        let lengths: Lengths = "4-6".parse().unwrap();
+        assert_eq!(vec![4, 5, 6], lengths.iter().collect::<Vec<_>>());
+        assert_eq!(
+            vec![7],
+            "7".parse::<Lengths>().unwrap().iter().collect::<Vec<_>>()
+        );
+        assert!("6-4".parse::<Lengths>().is_err());
+        assert!(WordSource::Wordle.with_letter_count(6).is_err());
+        let source: WordSource = "scrabble,5,100".parse().unwrap();
+        let wordlists = WordList::from_source_lengths(&source, lengths).unwrap();
+        assert_eq!(3, wordlists.len());
+        assert!(wordlists
+            .iter()
+            .zip(4..)
+            .all(|(w, n)| w.answers.len() == 100 && w.answers.iter().all(|a| a.len() == n)));
+    }
 }